@@ -0,0 +1,3856 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter, Write};
+use std::fs;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+use crossterm::style::{Color, Stylize};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use clap::ValueEnum;
+
+mod messages;
+pub use messages::{Language, Messages};
+
+pub const DEFAULT_BOARD_SIZE: usize = 10; //10 * 10 game board
+pub const MIN_BOARD_SIZE: usize = 5; //Smallest board the largest ship still fits on
+pub const MAX_BOARD_SIZE: usize = 26; //One letter per column
+const MAX_OCCUPANCY_PERCENT: usize = 75; //Past this a random layout is unlikely to be found at all
+const SHIP_ATTEMPTS: usize = 1000; //Random positions tried per ship before giving up on a layout
+const LAYOUT_ATTEMPTS: usize = 100; //Fresh starts before giving up on the fleet
+const SPREAD_CANDIDATES: usize = 20; //Valid spots compared per ship when spreading the fleet out
+const TEAM_BOARDS: usize = 2; //Boards on each side of a team game
+const RECENT_SHADES: [Color; 3] = [Color::White, Color::Grey, Color::DarkGrey]; //Outlines of the latest shots at a board, newest first
+const HEAT_GLYPHS: [&str; 5] = ["\u{00B7}", "\u{2591}", "\u{2592}", "\u{2593}", "\u{2588}"]; //From no placement at all to the most
+const HEAT_RAMP: [Color; 5] = [ //Viridis steps, which also read in both colour schemes
+    Color::Rgb { r: 68, g: 1, b: 84 },
+    Color::Rgb { r: 59, g: 82, b: 139 },
+    Color::Rgb { r: 33, g: 145, b: 140 },
+    Color::Rgb { r: 94, g: 201, b: 98 },
+    Color::Rgb { r: 253, g: 231, b: 37 },
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CellState {
+    Empty,
+    Ship,
+    Hit,
+    Miss,
+    Mine, //Costs whoever fires on it their next turn
+    Detonated, //A mine that was fired on
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub enum BoardVisibility {
+    Visible,
+    Hidden,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FireResult {
+    Hit,
+    Miss,
+    Sunk(ShipKind), //The shot hit the last intact cell of this ship
+    Mine, //The shooter loses their next turn
+    AlreadyFired, //The cell was hit or missed before, nothing changed
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ShipKind {
+    Destroyer,
+    Submarine,
+    Cruiser,
+    Battleship,
+    Carrier,
+}
+
+impl Display for ShipKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ShipKind::Destroyer => "Destroyer",
+            ShipKind::Submarine => "Submarine",
+            ShipKind::Cruiser => "Cruiser",
+            ShipKind::Battleship => "Battleship",
+            ShipKind::Carrier => "Carrier",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+pub type Fleet = Vec<(ShipKind, usize)>; //The ships placed on each board and their sizes
+
+pub const CLASSIC_FLEET: [(ShipKind, usize); 4] = [ //Also decides which name goes with which ship size
+    (ShipKind::Destroyer, 2),
+    (ShipKind::Submarine, 3),
+    (ShipKind::Battleship, 4),
+    (ShipKind::Carrier, 5),
+];
+
+pub const NAVY_FLEET: [(ShipKind, usize); 5] = [ //The five ship US Navy roster
+    (ShipKind::Carrier, 5),
+    (ShipKind::Battleship, 4),
+    (ShipKind::Cruiser, 3),
+    (ShipKind::Submarine, 3),
+    (ShipKind::Destroyer, 2),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum FleetSpecError {
+    NoShipOfSize(usize),
+    UnknownShip(String),
+}
+
+impl Display for FleetSpecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().fleet_spec_error)(self))
+    }
+}
+
+impl std::error::Error for FleetSpecError {}
+
+pub fn parse_fleet(input: &str) -> Result<Fleet, FleetSpecError> { //A preset name, or a comma separated list of ship names or sizes
+    match input {
+        "classic" => return Ok(CLASSIC_FLEET.to_vec()),
+        "navy" => return Ok(NAVY_FLEET.to_vec()),
+        _ => ()
+    }
+
+    input.split(',').map(|token| {
+        let token = token.trim().to_ascii_lowercase();
+
+        if let Ok(size) = token.parse::<usize>() { //Named after the largest classic ship that is not longer, check_fleet decides whether it fits
+            if size == 0 || size > MAX_BOARD_SIZE {
+                return Err(FleetSpecError::NoShipOfSize(size));
+            }
+            let kind = CLASSIC_FLEET.iter()
+                .rev()
+                .find(|&&(_, classic_size)| classic_size <= size)
+                .map_or(ShipKind::Destroyer, |&(kind, _)| kind);
+            return Ok((kind, size));
+        }
+
+        NAVY_FLEET.iter()
+            .find(|(kind, _)| kind.to_string().to_ascii_lowercase() == token)
+            .copied()
+            .ok_or(FleetSpecError::UnknownShip(token))
+    }).collect()
+}
+
+#[derive(Debug)]
+pub enum FleetError {
+    Empty,
+    ShipTooLong(ShipKind), //Longer than the board is wide
+    TooManyCells { needed: usize, available: usize },
+    TooCrowded { needed: usize, limit: usize }, //Fits on paper, but too tightly to place at random
+    NoRoomToSpace { needed: usize, available: usize }, //Fits touching, but there is no way to keep the ships apart
+    NoLayout, //Every placement attempt ran into a dead end
+}
+
+impl Display for FleetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().fleet_error)(self))
+    }
+}
+
+impl std::error::Error for FleetError {}
+
+pub fn check_fleet(fleet: &Fleet, rows: usize, columns: usize) -> Result<(), FleetError> {
+    if fleet.is_empty() {
+        return Err(FleetError::Empty);
+    }
+
+    if let Some(&(kind, _)) = fleet.iter().find(|&&(_, size)| size > rows.max(columns)) {
+        return Err(FleetError::ShipTooLong(kind));
+    }
+
+    let needed: usize = fleet.iter().map(|&(_, size)| size).sum();
+    let available = rows * columns;
+    if needed > available {
+        return Err(FleetError::TooManyCells { needed, available });
+    }
+
+    Ok(())
+}
+
+pub fn placement_audit((rows, columns): (usize, usize), rules: Rules, fleet: &Fleet, (runs, seed): (usize, u64)) -> Result<Vec<Vec<usize>>, FleetError> { //How often each cell held a ship over runs layouts from consecutive seeds
+    let mut counts = vec![vec![0; columns]; rows];
+    for layout_seed in (0..runs as u64).map(|i| seed.wrapping_add(i)) {
+        let mut board = Board::new(rows, columns, BoardVisibility::Visible, rules);
+        board.try_place_fleet(fleet, &mut StdRng::seed_from_u64(layout_seed))?;
+        for position in board.ships.iter().flat_map(|ship| &ship.cells) {
+            counts[position.row][position.column] += 1;
+        }
+    }
+
+    Ok(counts)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ship {
+    pub kind: ShipKind,
+    pub cells: Vec<Position>, //Every cell occupied by this ship
+    pub size: usize,
+}
+
+impl Ship {
+    pub fn is_sunk(&self, board: &Board) -> bool {
+        let (hits, size) = self.damage(board);
+        hits == size
+    }
+
+    pub fn damage(&self, board: &Board) -> (usize, usize) { //Cells hit so far, out of all its cells
+        let hits = self.cells.iter().filter(
+            |&position| board.grid[position.row][position.column] == CellState::Hit
+        ).count();
+
+        (hits, self.size)
+    }
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum AdjacencyRule { //Whether ships may be placed right next to each other
+    #[default]
+    Touching,
+    Corners, //Corner to corner is fine, side by side is not
+    NoTouch, //Not even diagonally
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum PlacementStrategy { //How random fleets are laid out
+    #[default]
+    Random,
+    Spread, //Prefer spots far away from the ships already placed
+    Interior, //Keep off the outer edge while there is room inside, the Hard computer's choice
+}
+
+#[derive(Copy, Clone, Default, Serialize, Deserialize)]
+pub struct Rules { //Optional rule variants, the default is the classic game
+    pub adjacency: AdjacencyRule,
+    #[serde(default)]
+    pub diagonal: bool, //Ships may also lie diagonally
+    #[serde(default)]
+    pub placement: PlacementStrategy,
+    #[serde(default)]
+    pub mines: usize, //Mines hidden in the water of each board, 0 to play without
+    #[serde(default)]
+    pub reveal_sunk: bool, //Sinking a ship also uncovers the water no other ship may take, meant for fleets kept apart
+    #[serde(default)]
+    pub fog: bool, //The enemy board shows nothing, not even misses, until the first hit on it
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Board {
+    grid: Vec<Vec<CellState>>, //Indexed by row, then column, read through cell and written through set_cell
+    #[serde(alias = "size")]
+    pub rows: usize,
+    #[serde(default)]
+    pub columns: usize, //Older saves only have a size, 0 means as many as rows
+    pub ships: Vec<Ship>,
+    pub board_visibility: BoardVisibility,
+    pub rules: Rules,
+    #[serde(skip)]
+    pub cursor: Option<Position>, //Cell highlighted while aiming with the arrow keys
+    #[serde(skip)]
+    pub preview: Option<(Vec<Position>, bool)>, //A ship being dragged into place, false if it cannot go there
+    #[serde(default)]
+    pub scanned: Vec<Position>, //Fired at by a scan, what was there stays hidden from the shooter
+    #[serde(default)]
+    pub peeked: Vec<Position>, //Ship cells uncovered by a peek, shown to the shooter but not hit
+    #[serde(skip)]
+    pub recent: Option<VecDeque<Position>>, //The latest shots at this board, newest first, None to not outline them
+    #[serde(skip)]
+    pub heat: Option<Vec<Vec<usize>>>, //Shades unfired cells by these counts instead of drawing them
+    #[serde(skip)]
+    pub column_labels: ColumnLabels,
+    #[serde(skip)]
+    pub style: DisplayStyle,
+    #[serde(skip)]
+    pub theme: RenderTheme,
+    #[serde(skip, default = "default_palette")]
+    pub palette: Option<Palette>, //None draws plain glyphs without colour
+}
+
+fn default_palette() -> Option<Palette> {
+    Some(Palette::STANDARD)
+}
+
+#[derive(Copy, Clone)]
+pub struct Palette { //Colours of the cells of a board
+    pub ship: Color,
+    pub water: Color,
+    pub hit: Color,
+    pub sunk: Color, //Hits on a ship that went down
+    pub miss: Color,
+    pub mine: Color,
+    pub warning: Color, //Messages such as the turn timer running out
+    pub preview: Color, //A ship being dragged to a spot where it fits
+    pub rejected: Color, //...or does not
+}
+
+impl Palette {
+    pub const STANDARD: Palette = Palette {
+        ship: Color::Green,
+        water: Color::DarkBlue,
+        hit: Color::Red,
+        sunk: Color::DarkRed,
+        miss: Color::Blue,
+        mine: Color::Yellow,
+        warning: Color::Yellow,
+        preview: Color::Green,
+        rejected: Color::Red,
+    };
+
+    pub const COLORBLIND: Palette = Palette { //Okabe-Ito colours, told apart without red-green vision
+        ship: Color::Rgb { r: 0, g: 114, b: 178 },
+        water: Color::DarkGrey,
+        hit: Color::Rgb { r: 230, g: 159, b: 0 },
+        sunk: Color::Rgb { r: 213, g: 94, b: 0 },
+        miss: Color::Rgb { r: 86, g: 180, b: 233 },
+        mine: Color::Rgb { r: 204, g: 121, b: 167 },
+        warning: Color::Rgb { r: 240, g: 228, b: 66 },
+        preview: Color::Rgb { r: 86, g: 180, b: 233 },
+        rejected: Color::Rgb { r: 213, g: 94, b: 0 },
+    };
+}
+
+#[derive(Copy, Clone, Default, ValueEnum)]
+pub enum ColorScheme {
+    #[default]
+    Standard,
+    Colorblind,
+}
+
+impl ColorScheme {
+    pub fn palette(self) -> Palette {
+        match self {
+            ColorScheme::Standard => Palette::STANDARD,
+            ColorScheme::Colorblind => Palette::COLORBLIND,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct RenderTheme { //The characters cells are drawn with
+    pub hidden: &'static str, //Anything the shooter has not found out yet
+    pub water: &'static str,
+    pub ship: &'static str,
+    pub hit: &'static str,
+    pub miss: &'static str,
+    pub sunk: &'static str,
+    pub mine: &'static str,
+    pub detonated: &'static str,
+    pub scanned: &'static str,
+    pub rejected: &'static str, //A dragged ship that does not fit
+    pub wide: bool, //Every glyph takes two columns, as emoji do
+    pub uncolored: Option<&'static RenderTheme>, //Used instead without colour, if the theme needs colour to tell cells apart
+}
+
+impl RenderTheme {
+    pub const UNICODE: RenderTheme = RenderTheme {
+        hidden: " ",
+        water: "\u{25A1}",
+        ship: "\u{25A0}",
+        hit: "\u{25CF}",
+        miss: "\u{25CF}",
+        sunk: "\u{25A0}",
+        mine: "\u{25C7}",
+        detonated: "\u{25C6}",
+        scanned: "?",
+        rejected: "\u{25A0}",
+        wide: false,
+        uncolored: Some(&RenderTheme::UNICODE_PLAIN),
+    };
+
+    const UNICODE_PLAIN: RenderTheme = RenderTheme {
+        hidden: " ",
+        water: "\u{25A1}",
+        ship: "\u{25A0}",
+        hit: "X",
+        miss: "o",
+        sunk: "\u{25A3}",
+        mine: "\u{25C7}",
+        detonated: "*",
+        scanned: "?",
+        rejected: "X",
+        wide: false,
+        uncolored: None,
+    };
+
+    pub const ASCII: RenderTheme = RenderTheme { //For terminals without the block characters
+        hidden: " ",
+        water: ".",
+        ship: "#",
+        hit: "X",
+        miss: "o",
+        sunk: "%",
+        mine: "+",
+        detonated: "*",
+        scanned: "?",
+        rejected: "!",
+        wide: false,
+        uncolored: None,
+    };
+
+    pub const EMOJI: RenderTheme = RenderTheme {
+        hidden: "  ",
+        water: "\u{1F30A}",
+        ship: "\u{1F6A2}",
+        hit: "\u{1F4A5}",
+        miss: "\u{1F4A6}",
+        sunk: "\u{1F525}",
+        mine: "\u{1F4A3}",
+        detonated: "\u{1F480}",
+        scanned: "\u{2753}",
+        rejected: "\u{26D4}",
+        wide: true,
+        uncolored: None,
+    };
+}
+
+impl Default for RenderTheme {
+    fn default() -> Self {
+        RenderTheme::UNICODE
+    }
+}
+
+#[derive(Copy, Clone, Default, ValueEnum)]
+pub enum GlyphSet {
+    #[default]
+    Unicode,
+    Ascii,
+    Emoji,
+}
+
+impl GlyphSet {
+    pub fn theme(self) -> RenderTheme {
+        match self {
+            GlyphSet::Unicode => RenderTheme::UNICODE,
+            GlyphSet::Ascii => RenderTheme::ASCII,
+            GlyphSet::Emoji => RenderTheme::EMOJI,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub enum ColumnLabels { //What goes across the top of the board
+    #[default]
+    Numbers,
+    Letters, //A, B, C... to match letter-number coordinates
+}
+
+#[derive(Copy, Clone, Default, ValueEnum)]
+pub enum DisplayStyle { //How the cells of a board are drawn
+    #[default]
+    Compact,
+    Grid, //Box-drawing lines around every cell
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum Orientation { //Denotes the orientation of the ship
+    Horizontal,
+    Vertical,
+    DiagonalDown, //Towards the bottom right
+    DiagonalUp, //Towards the top right
+}
+
+impl Orientation {
+    pub fn allowed(rules: &Rules) -> &'static [Orientation] {
+        if rules.diagonal {
+            &[Orientation::Horizontal, Orientation::Vertical, Orientation::DiagonalDown, Orientation::DiagonalUp]
+        } else {
+            &[Orientation::Horizontal, Orientation::Vertical]
+        }
+    }
+
+    pub fn cell(self, start: Position, i: usize) -> Option<Position> { //The i-th cell of a ship, None if it runs off the top
+        match self {
+            Orientation::Horizontal => Some(Position { row: start.row, column: start.column + i }),
+            Orientation::Vertical => Some(Position { row: start.row + i, column: start.column }),
+            Orientation::DiagonalDown => Some(Position { row: start.row + i, column: start.column + i }),
+            Orientation::DiagonalUp => Some(Position { row: start.row.checked_sub(i)?, column: start.column + i }),
+        }
+    }
+
+    fn back(self, end: Position, i: usize) -> Option<Position> { //The cell i steps before end, None if that is off the board
+        match self {
+            Orientation::Horizontal => Some(Position { row: end.row, column: end.column.checked_sub(i)? }),
+            Orientation::Vertical => Some(Position { row: end.row.checked_sub(i)?, column: end.column }),
+            Orientation::DiagonalDown => Some(Position { row: end.row.checked_sub(i)?, column: end.column.checked_sub(i)? }),
+            Orientation::DiagonalUp => Some(Position { row: end.row + i, column: end.column.checked_sub(i)? }),
+        }
+    }
+
+    pub fn between(a: Position, b: Position) -> Option<(Position, Orientation, usize)> { //The straight line from a to b as start, orientation and length
+        let (start, end) = if (b.column, b.row) < (a.column, a.row) { (b, a) } else { (a, b) }; //Ships are laid out rightwards or downwards
+        let (rows, columns) = (start.row.abs_diff(end.row), end.column - start.column);
+
+        if rows == 0 {
+            Some((start, Orientation::Horizontal, columns + 1))
+        } else if columns == 0 {
+            Some((start, Orientation::Vertical, rows + 1))
+        } else if rows == columns && end.row > start.row {
+            Some((start, Orientation::DiagonalDown, rows + 1))
+        } else if rows == columns {
+            Some((start, Orientation::DiagonalUp, rows + 1))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OutOfBounds(pub Position); //A shot at a cell the board does not have
+
+impl Display for OutOfBounds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().out_of_bounds)(self))
+    }
+}
+
+impl std::error::Error for OutOfBounds {}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Extend { //Which way a ship runs from the cell it is anchored at
+    Forward, //Like the orientation's own cells, rightwards or downwards
+    Backward,
+}
+
+impl Extend {
+    pub fn opposite(self) -> Extend {
+        match self {
+            Extend::Forward => Extend::Backward,
+            Extend::Backward => Extend::Forward,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PlacementError {
+    OffBoard,
+    Overlap,
+    TooClose, //Touches another ship while the no-touch rule is on
+    NoRoom, //Random placement gave up without finding a spot
+}
+
+impl Display for PlacementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().placement_error)(self))
+    }
+}
+
+impl std::error::Error for PlacementError {}
+
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    Empty,
+    RaggedRow { row: usize, length: usize }, //Not as long as the first row
+    UnknownCharacter { position: Position, character: char },
+    Bent(Position), //The ship with a cell here is not a straight line
+    NoShipOfSize(usize),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().template_error)(self))
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, PartialEq)]
+pub enum CompactError {
+    MissingPart, //Needs the size, the cells and the ships, split by '/'
+    BadSize(String),
+    NoCells, //A size of 0 rows or columns
+    TooLarge { rows: usize, columns: usize }, //Beyond MAX_BOARD_SIZE either way
+    UnknownCharacter(char),
+    WrongCellCount { expected: usize, found: usize },
+    BadShip(String), //Unreadable, or not on ship cells of the board
+}
+
+impl Display for CompactError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().compact_error)(self))
+    }
+}
+
+impl std::error::Error for CompactError {}
+
+const COMPACT_CELLS: [(CellState, char); 6] = [ //How each cell is written by Board::to_compact
+    (CellState::Empty, '.'),
+    (CellState::Ship, '#'),
+    (CellState::Hit, 'X'),
+    (CellState::Miss, 'o'),
+    (CellState::Mine, '*'),
+    (CellState::Detonated, '!'),
+];
+const COMPACT_KINDS: [ShipKind; 5] = [ShipKind::Destroyer, ShipKind::Submarine, ShipKind::Cruiser, ShipKind::Battleship, ShipKind::Carrier]; //Written as the index
+const COMPACT_ORIENTATIONS: [(Orientation, char); 4] = [
+    (Orientation::Horizontal, 'h'),
+    (Orientation::Vertical, 'v'),
+    (Orientation::DiagonalDown, 'd'),
+    (Orientation::DiagonalUp, 'u'),
+];
+
+impl Board {
+    pub fn from_template(template: &str) -> Result<Board, TemplateError> { //'#' for ship cells and '.' for water, one line per row
+        let lines: Vec<&str> = template.lines().map(str::trim).filter(|line| !line.is_empty()).collect();
+        let columns = lines.first().ok_or(TemplateError::Empty)?.chars().count();
+        let mut board = Board::new(lines.len(), columns, BoardVisibility::Visible, Rules::default());
+
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != columns {
+                return Err(TemplateError::RaggedRow { row, length: line.chars().count() });
+            }
+            for (column, character) in line.chars().enumerate() {
+                board.grid[row][column] = match character {
+                    '#' => CellState::Ship,
+                    '.' => CellState::Empty,
+                    _ => return Err(TemplateError::UnknownCharacter { position: Position { row, column }, character }),
+                };
+            }
+        }
+
+        //Every group of ship cells joined side by side is one ship, found in reading order
+        let mut owned = vec![vec![false; columns]; board.rows];
+        for start in (0..board.rows).flat_map(|row| (0..columns).map(move |column| Position { row, column })) {
+            if board.grid[start.row][start.column] != CellState::Ship || owned[start.row][start.column] {
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            let mut queue = VecDeque::from([start]);
+            owned[start.row][start.column] = true;
+            while let Some(cell) = queue.pop_front() {
+                cells.push(cell);
+                for next in [cell.row.checked_sub(1).map(|row| Position { row, ..cell }), Some(Position { row: cell.row + 1, ..cell }),
+                             cell.column.checked_sub(1).map(|column| Position { column, ..cell }), Some(Position { column: cell.column + 1, ..cell })] {
+                    let Some(next) = next.filter(|&next| board.contains(next)) else {
+                        continue;
+                    };
+                    if board.grid[next.row][next.column] == CellState::Ship && !owned[next.row][next.column] {
+                        owned[next.row][next.column] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if !cells.iter().all(|cell| cell.row == start.row) && !cells.iter().all(|cell| cell.column == start.column) {
+                return Err(TemplateError::Bent(start));
+            }
+            cells.sort_by_key(|cell| (cell.row, cell.column));
+
+            //Two ships of the same size take different names where the navy roster has them
+            let size = cells.len();
+            let taken = |kind: ShipKind| board.ships.iter().any(|ship| ship.kind == kind);
+            let kind = NAVY_FLEET.iter()
+                .find(|&&(kind, navy_size)| navy_size == size && !taken(kind))
+                .or_else(|| CLASSIC_FLEET.iter().find(|&&(_, classic_size)| classic_size == size))
+                .map(|&(kind, _)| kind)
+                .ok_or(TemplateError::NoShipOfSize(size))?;
+            board.ships.push(Ship { kind, cells, size });
+        }
+
+        Ok(board)
+    }
+
+    pub fn to_compact(&self) -> String { //One line for logs, e.g. 2x3/.#2.2X/0@0,1v2 with runs of the same cell counted
+        let cells: Vec<char> = self.grid.iter().flatten()
+            .map(|&state| COMPACT_CELLS.iter().find(|&&(cell, _)| cell == state).map_or('?', |&(_, character)| character))
+            .collect();
+        let mut runs = String::new();
+        for run in cells.chunk_by(|a, b| a == b) {
+            if run.len() > 1 {
+                runs.push_str(&run.len().to_string());
+            }
+            runs.push(run[0]);
+        }
+
+        let ships: Vec<String> = self.ships.iter().map(|ship| {
+            let kind = COMPACT_KINDS.iter().position(|&kind| kind == ship.kind).unwrap_or(0);
+            let start = ship.cells[0];
+            let orientation = match ship.cells.get(1) { //A one cell ship could lie either way
+                Some(next) if next.row == start.row => 'h',
+                Some(next) if next.column == start.column => 'v',
+                Some(next) if next.row > start.row => 'd',
+                Some(_) => 'u',
+                None => 'h',
+            };
+            format!("{}@{},{}{}{}", kind, start.row, start.column, orientation, ship.size)
+        }).collect();
+
+        format!("{}x{}/{}/{}", self.rows, self.columns, runs, ships.join(";"))
+    }
+
+    pub fn from_compact(line: &str) -> Result<Board, CompactError> { //Reads back Board::to_compact, the rules are the default ones
+        let mut parts = line.trim().split('/');
+        let (Some(size), Some(runs), Some(ships)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(CompactError::MissingPart);
+        };
+
+        let (rows, columns) = size.split_once('x')
+            .and_then(|(rows, columns)| Some((rows.parse().ok()?, columns.parse().ok()?)))
+            .ok_or_else(|| CompactError::BadSize(size.to_string()))?;
+        //Nothing is allocated before the size is known to be one a game can have
+        if rows == 0 || columns == 0 {
+            return Err(CompactError::NoCells);
+        }
+        if rows > MAX_BOARD_SIZE || columns > MAX_BOARD_SIZE {
+            return Err(CompactError::TooLarge { rows, columns });
+        }
+        let expected = rows.checked_mul(columns).ok_or(CompactError::TooLarge { rows, columns })?;
+
+        let mut cells = Vec::with_capacity(expected);
+        let mut count = String::new();
+        for character in runs.chars() {
+            if character.is_ascii_digit() {
+                count.push(character);
+                continue;
+            }
+
+            let &(state, _) = COMPACT_CELLS.iter().find(|&&(_, cell)| cell == character).ok_or(CompactError::UnknownCharacter(character))?;
+            let times: usize = if count.is_empty() { 1 } else { count.parse().map_err(|_| CompactError::UnknownCharacter(character))? };
+            let found = cells.len().saturating_add(times);
+            if found > expected { //A run past the end of the board
+                return Err(CompactError::WrongCellCount { expected, found });
+            }
+            cells.extend(std::iter::repeat_n(state, times));
+            count.clear();
+        }
+        if cells.len() != expected || !count.is_empty() {
+            return Err(CompactError::WrongCellCount { expected, found: cells.len() });
+        }
+
+        let mut board = Board::new(rows, columns, BoardVisibility::Visible, Rules::default());
+        for (index, state) in cells.into_iter().enumerate() {
+            board.grid[index / columns][index % columns] = state;
+        }
+
+        for ship in ships.split(';').filter(|ship| !ship.is_empty()) {
+            let bad_ship = || CompactError::BadShip(ship.to_string());
+            let (kind, rest) = ship.split_once('@').ok_or_else(bad_ship)?;
+            let kind = kind.parse::<usize>().ok().and_then(|kind| COMPACT_KINDS.get(kind).copied()).ok_or_else(bad_ship)?;
+            let split = rest.find(|c: char| c.is_ascii_alphabetic()).ok_or_else(bad_ship)?;
+            let (start, rest) = rest.split_at(split);
+            let mut rest = rest.chars();
+            let orientation = rest.next().and_then(|letter| COMPACT_ORIENTATIONS.iter().find(|&&(_, code)| code == letter)).map(|&(orientation, _)| orientation).ok_or_else(bad_ship)?;
+            let size: usize = rest.as_str().parse().map_err(|_| bad_ship())?;
+            let start: Position = start.parse().map_err(|_| bad_ship())?;
+
+            let cells: Vec<Position> = (0..size).filter_map(|i| orientation.cell(start, i)).collect();
+            let on_board = |cell: &Position| matches!(board.cell(*cell), Some(CellState::Ship | CellState::Hit));
+            if size == 0 || cells.len() != size || !cells.iter().all(on_board) {
+                return Err(bad_ship());
+            }
+            board.ships.push(Ship { kind, cells, size });
+        }
+
+        Ok(board)
+    }
+
+    pub fn new(rows: usize, columns: usize, board_visibility: BoardVisibility, rules: Rules) -> Self {
+        Board {
+            grid: vec![vec![CellState::Empty; columns]; rows],
+            rows,
+            columns,
+            ships: Vec::new(),
+            board_visibility,
+            rules,
+            cursor: None,
+            preview: None,
+            scanned: Vec::new(),
+            peeked: Vec::new(),
+            recent: None,
+            heat: None,
+            theme: RenderTheme::UNICODE,
+            column_labels: ColumnLabels::default(),
+            style: DisplayStyle::default(),
+            palette: default_palette(),
+        }
+    }
+
+    fn neighbours(&self, position: Position) -> impl Iterator<Item=Position> + '_ { //All 8 surrounding cells that are on the board
+        let rows = position.row.saturating_sub(1)..=(position.row + 1).min(self.rows - 1);
+
+        rows.flat_map(move |row| {
+            let columns = position.column.saturating_sub(1)..=(position.column + 1).min(self.columns - 1);
+            columns.map(move |column| Position { row, column })
+        }).filter(move |&neighbour| neighbour != position)
+    }
+
+    fn sides(&self, position: Position) -> impl Iterator<Item=Position> + '_ { //The 4 neighbours sharing an edge
+        self.neighbours(position).filter(move |neighbour| neighbour.row == position.row || neighbour.column == position.column)
+    }
+
+    pub fn border(&self, ship: &Ship) -> Vec<Position> { //The cells around a ship, clipped to the board
+        let mut border: Vec<Position> = Vec::new();
+        for &cell in &ship.cells {
+            for neighbour in self.neighbours(cell) {
+                if !ship.cells.contains(&neighbour) && !border.contains(&neighbour) {
+                    border.push(neighbour);
+                }
+            }
+        }
+
+        border
+    }
+
+    pub fn keep_clear(&self, ship: &Ship) -> Vec<Position> { //The cells around a ship no other ship may take under the adjacency rule
+        match self.rules.adjacency {
+            AdjacencyRule::Touching => Vec::new(),
+            AdjacencyRule::Corners => self.border(ship).into_iter()
+                .filter(|&cell| self.sides(cell).any(|side| ship.cells.contains(&side)))
+                .collect(),
+            AdjacencyRule::NoTouch => self.border(ship),
+        }
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        position.row < self.rows && position.column < self.columns
+    }
+
+    pub fn cell(&self, position: Position) -> Option<CellState> { //None off the board
+        self.contains(position).then(|| self.grid[position.row][position.column])
+    }
+
+    pub fn is_ship_at(&self, position: Position) -> bool { //A ship cell not hit yet, false off the board
+        self.cell(position) == Some(CellState::Ship)
+    }
+
+    pub fn set_cell(&mut self, position: Position, state: CellState) -> bool { //Overwrites the cell as is, ships are not kept in step, false off the board
+        if !self.contains(position) {
+            return false;
+        }
+
+        self.grid[position.row][position.column] = state;
+        true
+    }
+
+    fn check_cell(&self, position: Position) -> Result<(), PlacementError> { //Can a ship cell go here under the current rules
+        if self.grid[position.row][position.column] != CellState::Empty {
+            return Err(PlacementError::Overlap);
+        }
+
+        let too_close = match self.rules.adjacency {
+            AdjacencyRule::Touching => false,
+            AdjacencyRule::Corners => self.sides(position).any(|side| self.is_ship_at(side)),
+            AdjacencyRule::NoTouch => self.neighbours(position).any(|neighbour| self.is_ship_at(neighbour)),
+        };
+        if too_close {
+            Err(PlacementError::TooClose)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_place_fleet(&mut self, fleet: &Fleet, rng: &mut impl Rng) -> Result<(), FleetError> {
+        check_fleet(fleet, self.rows, self.columns)?;
+
+        //Without touching, grow every ship by a cell to the right and below, the water it keeps there.
+        //These blocks of (size + 1) * 2 cells or more never overlap and all lie on a board one row and
+        //column larger, so a fleet needing more than that cannot be kept apart at all
+        let (needed, area) = match self.rules.adjacency {
+            //Ships meeting at corners pack almost as tightly as touching ones
+            AdjacencyRule::Touching | AdjacencyRule::Corners => (fleet.iter().map(|&(_, size)| size).sum(), self.rows * self.columns),
+            AdjacencyRule::NoTouch => (fleet.iter().map(|&(_, size)| (size + 1) * 2).sum(), (self.rows + 1) * (self.columns + 1)),
+        };
+        if self.rules.adjacency == AdjacencyRule::NoTouch && needed > area {
+            return Err(FleetError::NoRoomToSpace { needed, available: area });
+        }
+        let limit = area * MAX_OCCUPANCY_PERCENT / 100;
+        if needed > limit {
+            return Err(FleetError::TooCrowded { needed, limit });
+        }
+
+        for _ in 0..LAYOUT_ATTEMPTS {
+            let placed = fleet.iter().all(|&(kind, size)| match self.rules.placement {
+                PlacementStrategy::Random => self.place_ship(kind, size, SHIP_ATTEMPTS, rng).is_ok(),
+                PlacementStrategy::Spread => self.place_ship_spread(kind, size, SHIP_ATTEMPTS, rng).is_ok(),
+                PlacementStrategy::Interior => self.place_ship_interior(kind, size, SHIP_ATTEMPTS, rng).is_ok(),
+            });
+            if placed {
+                return Ok(());
+            }
+
+            //An early ship boxed in a later one, start over on a clear board
+            self.grid = vec![vec![CellState::Empty; self.columns]; self.rows];
+            self.ships.clear();
+        }
+
+        Err(FleetError::NoLayout)
+    }
+
+    fn random_spot(&self, rng: &mut impl Rng) -> (Position, Orientation) { //Any start cell and allowed orientation, not checked
+        let position = Position {
+            row: rng.gen_range(0..self.rows),
+            column: rng.gen_range(0..self.columns),
+        };
+
+        let orientations = Orientation::allowed(&self.rules);
+        (position, orientations[rng.gen_range(0..orientations.len())])
+    }
+
+    pub fn place_ship(&mut self, kind: ShipKind, size: usize, attempts: usize, rng: &mut impl Rng) -> Result<(), PlacementError> { //size is the size of the ship
+        for _ in 0..attempts {
+            let (position, direction) = self.random_spot(rng);
+
+            if self.can_place(&position, size, direction) {
+                self.put_ship(kind, position, size, direction);
+                return Ok(());
+            }
+        }
+
+        Err(PlacementError::NoRoom)
+    }
+
+    pub fn place_ship_spread(&mut self, kind: ShipKind, size: usize, attempts: usize, rng: &mut impl Rng) -> Result<(), PlacementError> {
+        //Of several valid random spots, keep the one whose closest cell to any placed ship is furthest away
+        let placed: Vec<Position> = self.ships.iter().flat_map(|ship| ship.cells.iter().copied()).collect();
+        let mut best: Option<(usize, Position, Orientation)> = None;
+        let mut candidates = 0;
+
+        for _ in 0..attempts {
+            let (position, direction) = self.random_spot(rng);
+
+            if !self.can_place(&position, size, direction) {
+                continue;
+            }
+
+            let distance = (0..size)
+                .filter_map(|i| direction.cell(position, i))
+                .flat_map(|cell| placed.iter().map(move |other| cell.row.abs_diff(other.row) + cell.column.abs_diff(other.column)))
+                .min()
+                .unwrap_or(usize::MAX); //The first ship can go anywhere
+            if best.is_none_or(|(best_distance, _, _)| distance > best_distance) {
+                best = Some((distance, position, direction));
+            }
+
+            candidates += 1;
+            if candidates == SPREAD_CANDIDATES {
+                break;
+            }
+        }
+
+        let (_, position, direction) = best.ok_or(PlacementError::NoRoom)?;
+        self.put_ship(kind, position, size, direction);
+        Ok(())
+    }
+
+    pub fn place_ship_interior(&mut self, kind: ShipKind, size: usize, attempts: usize, rng: &mut impl Rng) -> Result<(), PlacementError> {
+        //Spots touching the edge are turned down, unless no spot inside turns up at all
+        let (rows, columns) = (self.rows, self.columns);
+        let inside = |cell: Position| cell.row > 0 && cell.column > 0 && cell.row + 1 < rows && cell.column + 1 < columns;
+
+        for _ in 0..attempts {
+            let (position, direction) = self.random_spot(rng);
+
+            if self.can_place(&position, size, direction) && (0..size).filter_map(|i| direction.cell(position, i)).all(inside) {
+                self.put_ship(kind, position, size, direction);
+                return Ok(());
+            }
+        }
+
+        self.place_ship(kind, size, attempts, rng) //Small boards have little room inside
+    }
+
+    pub fn place_mines(&mut self, count: usize, rng: &mut impl Rng) { //In empty water only, as many as fit
+        let mut water: Vec<Position> = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| Position { row, column }))
+            .filter(|position| self.grid[position.row][position.column] == CellState::Empty)
+            .collect();
+
+        for _ in 0..count.min(water.len()) {
+            let position = water.swap_remove(rng.gen_range(0..water.len()));
+            self.grid[position.row][position.column] = CellState::Mine;
+        }
+    }
+
+    pub fn reshuffle(&mut self, fleet: &Fleet, rng: &mut impl Rng) -> Result<(), FleetError> { //A fresh random fleet and mines, the old ones stay if no layout is found
+        let mut fresh = Board::new(self.rows, self.columns, self.board_visibility.clone(), self.rules);
+        fresh.try_place_fleet(fleet, rng)?;
+        fresh.place_mines(self.rules.mines, rng);
+
+        self.grid = fresh.grid;
+        self.ships = fresh.ships;
+        Ok(())
+    }
+
+    pub fn place_ship_at(&mut self, kind: ShipKind, position: Position, size: usize, orientation: Orientation) -> Result<(), PlacementError> {
+        self.check_placement(&position, size, orientation)?;
+        self.put_ship(kind, position, size, orientation);
+
+        Ok(())
+    }
+
+    //Lay a ship out from an anchor cell instead of its start, the cells it took are returned to echo them.
+    //With flip a ship that would run off the board is tried the other way before giving up
+    pub fn place_ship_towards(&mut self, kind: ShipKind, anchor: Position, size: usize, orientation: Orientation, extend: Extend, flip: bool) -> Result<Vec<Position>, PlacementError> {
+        let start = |extend: Extend| match extend {
+            Extend::Forward => Some(anchor),
+            Extend::Backward => orientation.back(anchor, size.saturating_sub(1)),
+        };
+        let placed = match start(extend) {
+            Some(start) => self.place_ship_at(kind, start, size, orientation),
+            None => Err(PlacementError::OffBoard),
+        };
+
+        match (placed, start(extend.opposite())) {
+            (Ok(()), _) => (),
+            (Err(PlacementError::OffBoard), Some(start)) if flip && self.contains(anchor) => self.place_ship_at(kind, start, size, orientation)?,
+            (Err(e), _) => return Err(e),
+        }
+
+        Ok(self.ships.last().expect("a ship was just placed").cells.clone())
+    }
+
+    fn put_ship(&mut self, kind: ShipKind, position: Position, size: usize, orientation: Orientation) { //Placement must already be checked
+        let mut cells = Vec::with_capacity(size);
+
+        for i in 0..size {
+            let cell = orientation.cell(position, i).expect("placement was checked");
+
+            self.grid[cell.row][cell.column] = CellState::Ship;
+            cells.push(cell);
+        }
+
+        self.ships.push(Ship { kind, cells, size });
+    }
+
+    pub fn can_place(&self, position: &Position, size: usize, orientation: Orientation) -> bool {
+        self.check_placement(position, size, orientation).is_ok()
+    }
+
+    pub fn valid_placements(&self, size: usize) -> Vec<(Position, Orientation)> { //Every start and orientation a ship of this size could still take
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| Position { row, column }))
+            .flat_map(|position| Orientation::allowed(&self.rules).iter().map(move |&orientation| (position, orientation)))
+            .filter(|(position, orientation)| self.can_place(position, size, *orientation))
+            .collect()
+    }
+
+    pub fn check_placement(&self, position: &Position, size: usize, orientation: Orientation) -> Result<(), PlacementError> {
+        if !self.contains(*position) {
+            return Err(PlacementError::OffBoard);
+        }
+
+        //Check the far end first so a ship running off the board is reported as such before any overlap
+        match orientation.cell(*position, size.saturating_sub(1)) {
+            Some(end) if self.contains(end) => (),
+            _ => return Err(PlacementError::OffBoard),
+        }
+
+        for i in 0..size {
+            self.check_cell(orientation.cell(*position, i).ok_or(PlacementError::OffBoard)?)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn fire(&mut self, position: Position) -> Result<FireResult, OutOfBounds> {
+        if !self.contains(position) { //E.g. from a save file or the network, which the board cannot vouch for
+            return Err(OutOfBounds(position));
+        }
+
+        let fresh = !already_fired(self, position);
+        if let Some(recent) = self.recent.as_mut().filter(|_| fresh) {
+            recent.push_front(position);
+            recent.truncate(RECENT_SHADES.len());
+        }
+
+        Ok(match self.grid[position.row][position.column] {
+            CellState::Empty => {
+                self.grid[position.row][position.column] = CellState::Miss;
+                FireResult::Miss
+            }
+            CellState::Ship => {
+                self.grid[position.row][position.column] = CellState::Hit;
+
+                let sunk = self.ships.iter()
+                    .find(|ship| ship.cells.contains(&position))
+                    .filter(|ship| ship.is_sunk(self));
+
+                match sunk {
+                    Some(ship) => {
+                        let kind = ship.kind;
+                        if self.rules.reveal_sunk {
+                            //Mines stay hidden, only plain water is given away
+                            for cell in self.keep_clear(ship) {
+                                if self.grid[cell.row][cell.column] == CellState::Empty {
+                                    self.grid[cell.row][cell.column] = CellState::Miss;
+                                }
+                            }
+                        }
+                        FireResult::Sunk(kind)
+                    }
+                    None => FireResult::Hit,
+                }
+            }
+            CellState::Mine => {
+                self.grid[position.row][position.column] = CellState::Detonated;
+                FireResult::Mine
+            }
+            CellState::Hit | CellState::Miss | CellState::Detonated => FireResult::AlreadyFired
+        })
+    }
+
+    pub fn fire_assisted(&mut self, position: Position) -> Result<(FireResult, Option<usize>), OutOfBounds> { //Also how many king's moves away the closest intact ship cell is, None once all are hit
+        let result = self.fire(position)?;
+        let distance = self.ships.iter()
+            .flat_map(|ship| ship.cells.iter())
+            .filter(|&&cell| self.is_ship_at(cell))
+            .map(|cell| cell.row.abs_diff(position.row).max(cell.column.abs_diff(position.column)))
+            .min();
+
+        Ok((result, distance))
+    }
+
+    pub fn line(&self, axis: Axis, index: usize) -> Vec<Position> { //Every cell of a row or column
+        match axis {
+            Axis::Row => (0..self.columns).map(|column| Position { row: index, column }).collect(),
+            Axis::Column => (0..self.rows).map(|row| Position { row, column: index }).collect(),
+        }
+    }
+
+    pub fn scan(&mut self, axis: Axis, index: usize) -> usize { //Fire at the whole line at once, the shooter only learns how many cells hit
+        self.scan_line(axis, index).iter().filter(|(_, result)| matches!(result, FireResult::Hit | FireResult::Sunk(_))).count()
+    }
+
+    pub fn scan_line(&mut self, axis: Axis, index: usize) -> Vec<(Position, FireResult)> { //The cells a scan fired at and what each of them was
+        let mut fired = Vec::new();
+
+        for position in self.line(axis, index) {
+            if already_fired(self, position) {
+                continue;
+            }
+            //Mines go off too, but a scan is no shot, so nobody loses a turn over it
+            let result = self.fire(position).expect("a line only runs over cells of the board");
+            fired.push((position, result));
+            self.scanned.push(position);
+        }
+
+        fired
+    }
+
+    pub fn peek(&mut self, rng: &mut impl Rng) -> Option<Position> { //Uncover one ship cell that was neither hit nor peeked at yet, nothing is fired
+        let hidden: Vec<Position> = self.ships.iter().flat_map(|ship| ship.cells.iter().copied())
+            .filter(|&cell| self.is_ship_at(cell) && !self.peeked.contains(&cell))
+            .collect();
+        if hidden.is_empty() {
+            return None;
+        }
+
+        let position = hidden[rng.gen_range(0..hidden.len())];
+        self.peeked.push(position);
+        Some(position)
+    }
+
+    pub fn fire_by_notation(&mut self, input: &str) -> Result<FireResult, ParseError> { //Fire at e.g. "C4" or "3, 2"
+        let position = parse_coordinates(input, self.rows, self.columns)?;
+        Ok(self.fire(position).expect("a parsed position is on the board"))
+    }
+
+    pub fn undo_fire(&mut self, position: Position) -> Result<(), OutOfBounds> { //Put a fired cell back the way it was before the shot
+        let cell = self.cell(position).ok_or(OutOfBounds(position))?;
+        if let Some(recent) = &mut self.recent {
+            recent.retain(|&shot| shot != position);
+        }
+
+        self.grid[position.row][position.column] = match cell {
+            CellState::Hit => CellState::Ship,
+            CellState::Miss => CellState::Empty,
+            CellState::Detonated => CellState::Mine,
+            other => other,
+        };
+        Ok(())
+    }
+
+    pub fn game_over(&self) -> bool {
+        //Once no ship cell is left unhit, every ship is sunk
+        self.ships.iter().flat_map(|ship| &ship.cells).all(|&cell| !self.is_ship_at(cell))
+    }
+
+    pub fn min_surviving_ship_size(&self) -> Option<usize> { //The smallest ship afloat sets the parity the hunt fires on, None once all are sunk
+        self.ships.iter()
+            .filter(|ship| !ship.is_sunk(self))
+            .map(|ship| ship.size)
+            .min()
+    }
+
+    pub fn validate(&self) -> Result<(), LoadError> { //Check a loaded board is consistent before it is used
+        if self.grid.len() != self.rows || self.grid.iter().any(|row| row.len() != self.columns) {
+            return Err(LoadError::GridSize);
+        }
+
+        let mut owned = vec![vec![false; self.columns]; self.rows];
+        for ship in &self.ships {
+            if ship.cells.len() != ship.size {
+                return Err(LoadError::ShipMismatch(ship.kind));
+            }
+
+            for cell in &ship.cells {
+                if !self.contains(*cell) {
+                    return Err(LoadError::ShipOffBoard(ship.kind));
+                }
+                if owned[cell.row][cell.column] {
+                    return Err(LoadError::ShipOverlap(ship.kind));
+                }
+                if !matches!(self.grid[cell.row][cell.column], CellState::Ship | CellState::Hit) {
+                    return Err(LoadError::ShipMismatch(ship.kind));
+                }
+                owned[cell.row][cell.column] = true;
+            }
+        }
+
+        //Every ship cell on the grid has to belong to a ship, or it could never be sunk
+        for (row, cells) in self.grid.iter().enumerate() {
+            for (column, &cell) in cells.iter().enumerate() {
+                if matches!(cell, CellState::Ship | CellState::Hit) && !owned[row][column] {
+                    return Err(LoadError::StrayShipCell(Position { row, column }));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn count(&self, state: CellState) -> usize { //Cells in this state anywhere on the board
+        self.grid.iter().flatten().filter(|&&cell| cell == state).count()
+    }
+
+    pub fn remaining_ship_cells(&self) -> usize { //Hits still needed to sink the whole fleet
+        self.count(CellState::Ship)
+    }
+
+    pub fn surviving_ships(&self) -> usize {
+        self.ships.iter().filter(|ship| !ship.is_sunk(self)).count()
+    }
+
+    pub fn unfired_positions(&self) -> Vec<Position> {
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| Position { row, column }))
+            .filter(|&position| !already_fired(self, position))
+            .collect()
+    }
+
+    pub fn heatmap(&self) -> Vec<Vec<usize>> { //How many placements of the ships still afloat cover each unfired cell
+        //Start over from what the shooter knows, misses and sunk ships are fixed and the rest could be anything
+        let mut known = Board::new(self.rows, self.columns, BoardVisibility::Visible, self.rules);
+        for (row, cells) in self.grid.iter().enumerate() {
+            for (column, &cell) in cells.iter().enumerate() {
+                if matches!(cell, CellState::Miss | CellState::Detonated) {
+                    known.grid[row][column] = CellState::Miss;
+                }
+            }
+        }
+        for ship in self.ships.iter().filter(|ship| ship.is_sunk(self)) {
+            for cell in &ship.cells {
+                known.grid[cell.row][cell.column] = CellState::Ship; //Also keeps no-touch placements away from it
+            }
+        }
+
+        let mut heat = vec![vec![0; self.columns]; self.rows];
+        for ship in self.ships.iter().filter(|ship| !ship.is_sunk(self)) {
+            for (position, orientation) in known.valid_placements(ship.size) {
+                for cell in (0..ship.size).filter_map(|i| orientation.cell(position, i)) {
+                    if !already_fired(self, cell) {
+                        heat[cell.row][cell.column] += 1;
+                    }
+                }
+            }
+        }
+
+        heat
+    }
+
+    pub fn fogged(&self) -> bool { //Drawn blank to the shooter, no contact with the fleet yet
+        self.rules.fog && matches!(self.board_visibility, BoardVisibility::Hidden)
+            && !self.grid.iter().flatten().any(|&cell| cell == CellState::Hit)
+    }
+
+    pub fn unfired_cells(&self) -> usize {
+        self.grid.iter().flatten()
+            .filter(|&&cell| !matches!(cell, CellState::Hit | CellState::Miss | CellState::Detonated))
+            .count()
+    }
+}
+
+impl Board {
+    fn glyph(&self, position: Position) -> String { //One cell, three characters wide
+        //Without colour, hits and misses may need glyphs of their own
+        let theme = match (&self.palette, self.theme.uncolored) {
+            (None, Some(plain)) => plain,
+            _ => &self.theme,
+        };
+        let paint = |glyph: &str, color: fn(&Palette) -> Color| match &self.palette {
+            Some(palette) => glyph.with(color(palette)).to_string(),
+            None => glyph.to_string(),
+        };
+        let hidden = matches!(self.board_visibility, BoardVisibility::Hidden);
+
+        let glyph = match self.grid[position.row][position.column] {
+            CellState::Empty | CellState::Ship | CellState::Mine if hidden => theme.hidden.to_string(),
+            CellState::Empty => paint(theme.water, |palette| palette.water),
+            CellState::Ship => paint(theme.ship, |palette| palette.ship),
+            CellState::Mine => paint(theme.mine, |palette| palette.mine),
+            //Worked out here rather than stored, a hit only turns into a sunk ship with the ship's last cell
+            CellState::Hit if self.ships.iter().any(|ship| ship.cells.contains(&position) && ship.is_sunk(self)) => {
+                paint(theme.sunk, |palette| palette.sunk)
+            }
+            CellState::Hit => paint(theme.hit, |palette| palette.hit),
+            CellState::Miss => paint(theme.miss, |palette| palette.miss),
+            CellState::Detonated => paint(theme.detonated, |palette| palette.mine)
+        };
+
+        let glyph = if self.fogged() {
+            theme.hidden.to_string()
+        } else if hidden && self.scanned.contains(&position) {
+            paint(theme.scanned, |palette| palette.warning)
+        } else if hidden && self.peeked.contains(&position) && self.is_ship_at(position) {
+            paint(theme.ship, |palette| palette.warning)
+        } else {
+            glyph
+        };
+
+        let glyph = match &self.heat {
+            Some(heat) if !already_fired(self, position) => {
+                let max = heat.iter().flatten().copied().max().unwrap_or(0).max(1);
+                let count = heat[position.row][position.column];
+                let level = (count * (HEAT_GLYPHS.len() - 1)).div_ceil(max); //Only a cell nothing fits on gets the lowest step
+                let shade = HEAT_GLYPHS[level].repeat(if theme.wide { 2 } else { 1 });
+                match &self.palette {
+                    Some(_) => shade.with(HEAT_RAMP[level]).to_string(),
+                    None => shade,
+                }
+            }
+            _ => glyph,
+        };
+
+        let glyph = match &self.preview {
+            Some((cells, true)) if cells.contains(&position) => paint(theme.ship, |palette| palette.preview),
+            Some((cells, false)) if cells.contains(&position) => paint(theme.rejected, |palette| palette.rejected),
+            _ => glyph,
+        };
+
+        //A wide glyph already takes two of the three characters, so it only gets the left mark
+        let right = |mark: String| if theme.wide { String::new() } else { mark };
+        let age = self.recent.as_ref().and_then(|recent| recent.iter().position(|&shot| shot == position));
+        match age {
+            _ if self.cursor == Some(position) => format!("[{}{}", glyph, right("]".to_string())),
+            Some(_) if self.fogged() => format!(" {}{}", glyph, right(" ".to_string())), //Not even where the last shots went
+            Some(age) => match &self.palette {
+                Some(_) => format!("{}{}{}", "(".with(RECENT_SHADES[age]), glyph, right(")".with(RECENT_SHADES[age]).to_string())),
+                None => format!("({}{}", glyph, right(")".to_string())), //No shades to fade with
+            },
+            None => format!(" {}{}", glyph, right(" ".to_string())),
+        }
+    }
+
+    pub fn cell_at(&self, x: usize, y: usize) -> Option<Position> { //Which cell is drawn at a character offset from the board's top left corner
+        let label_width = (self.rows - 1).to_string().len();
+
+        let (row, column) = match self.style {
+            DisplayStyle::Compact => (y.checked_sub(1)?, x.checked_sub(label_width + 1)? / 3),
+            DisplayStyle::Grid => {
+                let (row, column) = (y.checked_sub(2)?, x.checked_sub(label_width + 2)?);
+                if row % 2 == 1 || column % 4 == 3 { //On a line between cells
+                    return None;
+                }
+                (row / 2, column / 4)
+            }
+        };
+
+        let position = Position { row, column };
+        self.contains(position).then_some(position)
+    }
+
+    pub fn column_label(&self, column: usize) -> String {
+        match self.column_labels {
+            ColumnLabels::Numbers => column.to_string(),
+            ColumnLabels::Letters => column_letter(column).to_string(),
+        }
+    }
+
+    fn fmt_compact(&self, f: &mut impl Write, label_width: usize) -> std::fmt::Result {
+        write!(f, "{:width$}", "", width = label_width + 1)?;
+        for column in 0..self.columns {
+            write!(f, "{:^3}", self.column_label(column))?;
+        }
+        writeln!(f)?;
+
+        for row in 0..self.rows {
+            write!(f, "{:>width$} ", row, width = label_width)?;
+            for column in 0..self.columns {
+                write!(f, "{}", self.glyph(Position { row, column }))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+
+    fn fmt_grid(&self, f: &mut impl Write, label_width: usize) -> std::fmt::Result {
+        let border = |left: char, middle: char, right: char| {
+            format!("{:width$}{}{}{}", "", left, vec!["\u{2500}".repeat(3); self.columns].join(&middle.to_string()), right, width = label_width + 1)
+        };
+
+        write!(f, "{:width$}", "", width = label_width + 1)?;
+        for column in 0..self.columns { //Each label sits over the middle of its cell, past the vertical line
+            write!(f, " {:^3}", self.column_label(column))?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "{}", border('\u{250C}', '\u{252C}', '\u{2510}'))?;
+        for row in 0..self.rows {
+            write!(f, "{:>width$} \u{2502}", row, width = label_width)?;
+            for column in 0..self.columns {
+                write!(f, "{}\u{2502}", self.glyph(Position { row, column }))?;
+            }
+            writeln!(f)?;
+
+            if row + 1 < self.rows {
+                writeln!(f, "{}", border('\u{251C}', '\u{253C}', '\u{2524}'))?;
+            }
+        }
+        writeln!(f, "{}", border('\u{2514}', '\u{2534}', '\u{2518}'))
+    }
+}
+
+impl Board {
+    pub fn to_ascii(&self) -> String { //Plain characters without colours, for pasting into bug reports
+        let label_width = (self.rows - 1).to_string().len();
+        let mut text = format!("{:width$}", "", width = label_width + 1);
+
+        for column in 0..self.columns {
+            text.push_str(&format!("{:^3}", self.column_label(column)));
+        }
+        text.push('\n');
+
+        let fogged = self.fogged();
+        for (row, cells) in self.grid.iter().enumerate() {
+            text.push_str(&format!("{:>width$} ", row, width = label_width));
+            for (column, cell) in cells.iter().enumerate() {
+                let hidden = matches!(self.board_visibility, BoardVisibility::Hidden);
+                let glyph = match cell {
+                    _ if fogged => '.',
+                    _ if hidden && self.scanned.contains(&Position { row, column }) => '?',
+                    CellState::Ship if self.peeked.contains(&Position { row, column }) => '#',
+                    CellState::Ship if matches!(self.board_visibility, BoardVisibility::Visible) => '#',
+                    CellState::Mine if matches!(self.board_visibility, BoardVisibility::Visible) => '+',
+                    CellState::Empty | CellState::Ship | CellState::Mine => '.',
+                    CellState::Hit => 'X',
+                    CellState::Miss => 'o',
+                    CellState::Detonated => '*',
+                };
+                text.push_str(&format!(" {} ", glyph));
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+pub trait BoardRenderer { //Turns a board into text for one kind of frontend
+    fn render(&self, board: &Board) -> String;
+}
+
+pub struct TerminalRenderer; //Colours, the theme's glyphs and the board's display style
+
+impl BoardRenderer for TerminalRenderer {
+    fn render(&self, board: &Board) -> String {
+        let label_width = (board.rows - 1).to_string().len(); //Row labels may need two digits
+        let mut text = String::new();
+
+        let _ = match board.style { //Writing to a String cannot fail
+            DisplayStyle::Compact => board.fmt_compact(&mut text, label_width),
+            DisplayStyle::Grid => board.fmt_grid(&mut text, label_width),
+        };
+        text
+    }
+}
+
+pub struct AsciiRenderer; //Plain characters only, see Board::to_ascii
+
+impl BoardRenderer for AsciiRenderer {
+    fn render(&self, board: &Board) -> String {
+        board.to_ascii()
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&TerminalRenderer.render(self))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Axis {
+    Row,
+    Column,
+}
+
+impl Display for Axis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Axis::Row => write!(f, "row"),
+            Axis::Column => write!(f, "column"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    WrongFieldCount(usize), //How many comma separated fields were given
+    NotANumber(String),
+    OutOfRange { axis: Axis, value: usize },
+    LetterOutOfRange(char), //A letter past the last column of the board
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().parse_error)(self))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn column_letter(column: usize) -> char {
+    (b'A' + column as u8) as char
+}
+
+fn parse_number(token: &str, axis: Axis, limit: usize) -> Result<usize, ParseError> { //limit is the number of rows or columns
+    let value = token.parse().map_err(|_| ParseError::NotANumber(token.to_string()))?;
+
+    if value >= limit {
+        return Err(ParseError::OutOfRange { axis, value });
+    }
+
+    Ok(value)
+}
+
+pub const SHORTCUTS: [(char, &str); 4] = [('h', "history"), ('r', "radar"), ('s', "save"), ('q', "quit")]; //Keys that stand for a command at the fire prompt
+
+pub fn expand_shortcut<'a>(input: &'a str, shortcuts: &[(char, &'a str)]) -> &'a str { //Only a line of just the key, so no command that starts with it is taken for it
+    let mut chars = input.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some(key), None) => shortcuts.iter().find(|&&(shortcut, _)| shortcut == key).map_or(input, |&(_, command)| command),
+        _ => input,
+    }
+}
+
+pub fn parse_coordinates(input: &str, rows: usize, columns: usize) -> Result<Position, ParseError> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    if input.contains(',') {
+        let fields: Vec<&str> = input.split(',').map(str::trim).collect();
+
+        return match fields[..] {
+            [row, column] => Ok(Position {
+                row: parse_number(row, Axis::Row, rows)?,
+                column: parse_number(column, Axis::Column, columns)?,
+            }),
+            _ => Err(ParseError::WrongFieldCount(fields.len())),
+        };
+    }
+
+    //Letter-number notation: the letter picks the column, the number the row, e.g. "B7" or "b 7"
+    let mut chars = input.chars();
+    let letter = match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+        Some(c) if c.is_ascii_digit() => return Err(ParseError::WrongFieldCount(1)),
+        _ => return Err(ParseError::NotANumber(input.to_string())),
+    };
+
+    let column = (letter as u8 - b'A') as usize;
+    if column >= columns {
+        return Err(ParseError::LetterOutOfRange(letter));
+    }
+
+    let row = chars.as_str().trim();
+    if row.is_empty() {
+        return Err(ParseError::WrongFieldCount(1));
+    }
+
+    Ok(Position { row: parse_number(row, Axis::Row, rows)?, column })
+}
+
+pub fn parse_coordinates_multi(input: &str, rows: usize, columns: usize) -> Vec<Result<Position, ParseError>> { //Several cells on one line, e.g. "A1 B2 3, 4", one result each
+    //Split at the spaces between cells, not the ones inside "3, 4" or "b 7"
+    let mut cells: Vec<String> = Vec::new();
+    for token in input.split_whitespace() {
+        let joins = cells.last().is_some_and(|last| {
+            let complete = last.trim_end_matches(',').parse::<Position>().is_ok(); //Then a comma after it only separates, as in "A1, B2"
+            let lone_letter = last.len() == 1 && last.chars().all(|c| c.is_ascii_alphabetic());
+            !complete && (last.ends_with(',') || token.starts_with(',') || (lone_letter && token.chars().all(|c| c.is_ascii_digit())))
+        });
+        match cells.last_mut() {
+            Some(last) if joins => last.push_str(token),
+            _ => cells.push(token.to_string()),
+        }
+    }
+
+    cells.iter().map(|cell| parse_coordinates(cell.trim_matches(','), rows, columns)).collect()
+}
+
+impl FromStr for Position { //Like parse_coordinates, but on a board of any size, e.g. "3,4" or "D3"
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_coordinates(input, usize::MAX, usize::MAX)
+    }
+}
+
+pub trait OpponentStrategy { //How the computer picks where to fire
+    fn next_move(&mut self, board: &Board) -> Option<Position>; //Never a fired cell, None once every cell has been fired at
+
+    fn record(&mut self, _position: Position, _result: FireResult) {} //Feed back the result of the last shot
+
+    fn state(&self) -> AiState { //What needs to be saved to pick up where the strategy left off
+        AiState::default()
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct AiState {
+    pub targets: Vec<Position>,
+    pub last_hit: Option<Position>,
+    #[serde(default)]
+    pub sunk: Vec<Position>, //The shot that finished off each ship sunk so far
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn strategy(&self, rng: StdRng, state: AiState) -> Box<dyn OpponentStrategy> {
+        match self {
+            Difficulty::Easy => Box::new(RandomStrategy { rng }),
+            Difficulty::Medium => Box::new(HuntTargetStrategy::new(rng, state)),
+            Difficulty::Hard => Box::new(ProbabilityStrategy { rng }),
+        }
+    }
+}
+
+pub struct RandomStrategy { //Fires at any fresh cell, never follows up on a hit
+    rng: StdRng,
+}
+
+impl OpponentStrategy for RandomStrategy {
+    fn next_move(&mut self, board: &Board) -> Option<Position> {
+        let unfired = board.unfired_positions();
+        if unfired.is_empty() {
+            return None;
+        }
+
+        Some(unfired[self.rng.gen_range(0..unfired.len())])
+    }
+}
+
+pub struct HuntTargetStrategy {
+    targets: VecDeque<Position>, //Cells queued up around a hit, fired at before hunting again
+    last_hit: Option<Position>,
+    sunk: Vec<Position>, //Where each ship went down, to find its border again
+    rng: StdRng,
+}
+
+impl HuntTargetStrategy {
+    pub fn new(rng: StdRng, state: AiState) -> Self {
+        HuntTargetStrategy {
+            targets: state.targets.into(),
+            last_hit: state.last_hit,
+            sunk: state.sunk,
+            rng,
+        }
+    }
+
+    fn excluded(&self, board: &Board) -> Vec<Position> { //Cells around the ships we sank, where no ship can be if they may not touch
+        board.ships.iter()
+            .filter(|ship| self.sunk.iter().any(|position| ship.cells.contains(position)))
+            .flat_map(|ship| board.keep_clear(ship))
+            .collect()
+    }
+}
+
+impl OpponentStrategy for HuntTargetStrategy {
+    fn next_move(&mut self, board: &Board) -> Option<Position> {
+        let excluded = self.excluded(board);
+
+        //Target mode: work through the cells around previous hits
+        while let Some(position) = self.targets.pop_front() { //Targets past the far edges are only dropped here
+            if board.contains(position) && !already_fired(board, position) && !excluded.contains(&position) {
+                return Some(position);
+            }
+        }
+        self.last_hit = None; //Nothing left to follow up, back to hunting
+
+        //Hunt mode: every ship covers one cell of a diagonal stripe as wide as the smallest
+        //ship still afloat, so only those cells need to be searched
+        let stride = board.min_surviving_ship_size().unwrap_or(1);
+        let unfired: Vec<Position> = board.unfired_positions().into_iter().filter(|position| !excluded.contains(position)).collect();
+        let parity: Vec<Position> = unfired.iter()
+            .copied()
+            .filter(|position| (position.row + position.column) % stride == 0)
+            .collect();
+
+        let candidates = if parity.is_empty() { unfired } else { parity };
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Some(candidates[self.rng.gen_range(0..candidates.len())])
+    }
+
+    fn record(&mut self, position: Position, result: FireResult) {
+        match result {
+            FireResult::Miss | FireResult::Mine | FireResult::AlreadyFired => return,
+            FireResult::Sunk(_) => { //The ship we were chasing is gone, go back to hunting
+                self.targets.clear();
+                self.last_hit = None;
+                self.sunk.push(position);
+                return;
+            }
+            FireResult::Hit => ()
+        }
+
+        let Position { row, column } = position;
+
+        //Two hits in a line: try continuing along that line before anything else
+        if let Some(last) = self.last_hit {
+            if last.row == row && last.column.abs_diff(column) == 1 {
+                if let Some(next) = if column > last.column { column.checked_add(1) } else { column.checked_sub(1) } {
+                    self.targets.push_front(Position { row, column: next });
+                }
+            } else if last.column == column && last.row.abs_diff(row) == 1 {
+                if let Some(next) = if row > last.row { row.checked_add(1) } else { row.checked_sub(1) } {
+                    self.targets.push_front(Position { row: next, column });
+                }
+            }
+        }
+        self.last_hit = Some(position);
+
+        if row > 0 {
+            self.targets.push_back(Position { row: row - 1, column });
+        }
+        self.targets.push_back(Position { row: row + 1, column });
+        if column > 0 {
+            self.targets.push_back(Position { row, column: column - 1 });
+        }
+        self.targets.push_back(Position { row, column: column + 1 });
+    }
+
+    fn state(&self) -> AiState {
+        AiState {
+            targets: self.targets.iter().copied().collect(),
+            last_hit: self.last_hit,
+            sunk: self.sunk.clone(),
+        }
+    }
+}
+
+const HIT_WEIGHT: usize = 20; //How much more likely a placement is when it runs through an unexplained hit
+
+pub struct ProbabilityStrategy { //Fires where the remaining ships could fit in the most ways
+    rng: StdRng,
+}
+
+impl OpponentStrategy for ProbabilityStrategy {
+    fn next_move(&mut self, board: &Board) -> Option<Position> {
+        let best = best_guesses(board);
+        if best.is_empty() {
+            return None;
+        }
+
+        Some(best[self.rng.gen_range(0..best.len())])
+    }
+}
+
+pub fn best_guesses(board: &Board) -> Vec<Position> { //The unfired cells the most placements run through, the Hard computer picks one of them
+    let density = probability_density(board);
+
+    let mut best = Vec::new();
+    let mut best_weight = 0;
+    for (row, weights) in density.iter().enumerate() {
+        for (column, &weight) in weights.iter().enumerate() {
+            let position = Position { row, column };
+            if already_fired(board, position) {
+                continue;
+            }
+
+            if weight > best_weight {
+                best_weight = weight;
+                best.clear();
+            }
+            if weight == best_weight {
+                best.push(position);
+            }
+        }
+    }
+
+    best
+}
+
+fn probability_density(board: &Board) -> Vec<Vec<usize>> {
+    //Cells of sunk ships are accounted for, every other hit still belongs to a ship that is afloat
+    let sunk_cells: Vec<Position> = board.ships.iter()
+        .filter(|ship| ship.is_sunk(board))
+        .flat_map(|ship| ship.cells.iter().copied())
+        .collect();
+    //Nor can any ship lie right next to a sunk one when they may not touch
+    let sunk_borders: Vec<Position> = board.ships.iter().filter(|ship| ship.is_sunk(board)).flat_map(|ship| board.keep_clear(ship)).collect();
+
+    let mut density = vec![vec![0; board.columns]; board.rows];
+
+    for ship in board.ships.iter().filter(|ship| !ship.is_sunk(board)) {
+        for row in 0..board.rows {
+            for column in 0..board.columns {
+                for &orientation in Orientation::allowed(&board.rules) {
+                    let Some(cells) = (0..ship.size)
+                        .map(|i| orientation.cell(Position { row, column }, i))
+                        .collect::<Option<Vec<Position>>>() else {
+                        continue; //Runs off the top of the board
+                    };
+
+                    let fits = cells.iter().all(|&cell| {
+                        board.contains(cell)
+                            && !matches!(board.grid[cell.row][cell.column], CellState::Miss | CellState::Detonated)
+                            && !sunk_cells.contains(&cell)
+                            && !sunk_borders.contains(&cell)
+                    });
+                    if !fits {
+                        continue;
+                    }
+
+                    let hits = cells.iter() //A peeked cell is as good as a hit, the ship is known to be there
+                        .filter(|&&cell| board.grid[cell.row][cell.column] == CellState::Hit || (board.peeked.contains(&cell) && board.is_ship_at(cell)))
+                        .count();
+                    let weight = 1 + HIT_WEIGHT * hits;
+
+                    for cell in cells {
+                        density[cell.row][cell.column] += weight;
+                    }
+                }
+            }
+        }
+    }
+
+    density
+}
+
+pub fn already_fired(board: &Board, position: Position) -> bool { //False off the board
+    board.cell(position).is_some_and(|cell| matches!(cell, CellState::Hit | CellState::Miss | CellState::Detonated))
+}
+
+pub fn opponent_salvo(strategy: &mut dyn OpponentStrategy, board: &Board, shots: usize) -> Vec<Position> {
+    //All shots of a salvo are picked before any of them lands, so the strategy sees the
+    //earlier picks as already fired at
+    let mut scratch = board.clone();
+    let mut salvo = Vec::with_capacity(shots);
+
+    for _ in 0..shots {
+        let Some(position) = strategy.next_move(&scratch) else {
+            break; //The whole board has been fired at
+        };
+        scratch.grid[position.row][position.column] = CellState::Miss;
+        salvo.push(position);
+    }
+
+    salvo
+}
+
+#[derive(Copy, Clone)]
+pub enum PlayerPlacement {
+    Random,
+    Manual, //The player positions their own fleet before the game starts
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, ValueEnum)]
+pub enum GameMode {
+    Classic,
+    Salvo, //One shot per surviving ship each turn
+    Hotseat, //Two humans taking turns on one terminal
+    Teams, //Two boards a side, each shot picks one of the enemy boards
+    Chain, //A hit earns another shot, the turn only passes on a miss
+    #[value(skip)]
+    Network, //Against another process over TCP, chosen with --host or --connect
+}
+
+impl GameMode {
+    pub fn shots(&self, shooter: &Board) -> usize { //How many shots the owner of this board gets this turn
+        match self {
+            GameMode::Classic | GameMode::Hotseat | GameMode::Teams | GameMode::Chain | GameMode::Network => 1,
+            GameMode::Salvo => shooter.surviving_ships(),
+        }
+    }
+
+    pub fn fires_again(&self, result: FireResult) -> bool { //Whether this shot keeps the turn with the shooter
+        *self == GameMode::Chain && matches!(result, FireResult::Hit | FireResult::Sunk(_))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Player {
+    Human,
+    Computer,
+}
+
+#[derive(Copy, Clone, PartialEq, ValueEnum)]
+pub enum Notation { //How positions are written back to the player
+    Numeric, //3,4
+    Letter, //E3, the letter is the column
+}
+
+impl Notation {
+    pub fn format(&self, position: Position) -> String {
+        match self {
+            Notation::Numeric => format!("{},{}", position.row, position.column),
+            Notation::Letter => format!("{}{}", column_letter(position.column), position.row),
+        }
+    }
+}
+
+pub const HIT_POINTS: i64 = 10;
+pub const MISS_PENALTY: i64 = 2; //Mines cost the same as any other miss
+pub const STREAK_BONUS: i64 = 5; //For each hit in a row before the shot that sinks a ship
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub shots: usize,
+    pub hits: usize,
+    pub misses: usize,
+    pub turns: usize,
+    pub score: i64,
+    #[serde(skip)]
+    pub streak: usize, //Hits since the last miss
+    #[serde(skip)]
+    pub think_times: Vec<Duration>, //From the prompt to the typed shot, only for human players
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThinkTime {
+    pub average: Duration,
+    pub fastest: Duration,
+    pub slowest: Duration,
+}
+
+impl Stats {
+    pub fn record(&mut self, result: FireResult) {
+        match result {
+            FireResult::Hit => {
+                self.hits += 1;
+                self.score += HIT_POINTS;
+                self.streak += 1;
+            }
+            FireResult::Sunk(_) => {
+                self.hits += 1;
+                self.score += HIT_POINTS + STREAK_BONUS * self.streak as i64;
+                self.streak += 1;
+            }
+            FireResult::Miss | FireResult::Mine => {
+                self.misses += 1;
+                self.score -= MISS_PENALTY;
+                self.streak = 0;
+            }
+            FireResult::AlreadyFired => return, //Not a real shot
+        }
+        self.shots += 1;
+    }
+
+    pub fn think_time(&self) -> Option<ThinkTime> { //None until a shot has been timed
+        Some(ThinkTime {
+            average: self.think_times.iter().sum::<Duration>() / self.think_times.len().max(1) as u32,
+            fastest: *self.think_times.iter().min()?,
+            slowest: *self.think_times.iter().max()?,
+        })
+    }
+
+    pub fn accuracy(&self) -> f64 { //Percentage of shots that hit
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / self.shots as f64
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().stats)(self))
+    }
+}
+
+pub fn date_seed(days: u64) -> u64 { //YYYYMMDD of the day this many days after 1970-01-01, the same seed for everyone that day
+    //Civil date from a day count, with years starting in March so the leap day comes last
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    year * 10_000 + month * 100 + day
+}
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum InputAction { //What the player chose to do at the fire prompt
+    Fire(Position),
+    Queued(Position), //Fired like Fire, but typed ahead with burst, so not timed
+    Scan(Axis, usize), //Spend a scan on this row or column
+    Quit,
+    Surrender,
+    TimeUp, //The turn timer ran out first
+    Auto, //Fire where the Hard computer would
+    Undo, //Take back our last shot, while the opponent has not answered it
+}
+
+pub struct TurnOutcome { //What came of one Game::step
+    pub shots: Vec<(Player, Position, FireResult)>, //Every shot fired, ours first and then the computer's reply
+    pub scanned: Option<usize>, //Ship cells hit by a scan
+    pub off_board: Option<OutOfBounds>, //The shot was not on the board and the step did nothing
+    pub distance: Option<usize>, //How close our shot came to an intact ship cell
+    pub undone: Option<Position>, //The shot taken back by an undo
+    pub over: bool, //Nobody plays on, also after quitting or once the turns ran out
+    pub winner: Option<Player>,
+    pub turn: Player, //Who acts next
+}
+
+impl TurnOutcome {
+    fn new(turn: Player) -> TurnOutcome { //Nothing happened yet
+        TurnOutcome { shots: Vec::new(), scanned: None, off_board: None, distance: None, undone: None, over: false, winner: None, turn }
+    }
+}
+
+pub struct Game {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub player_board: Board,
+    pub computer_board: Board,
+    pub opponent: Box<dyn OpponentStrategy>,
+    pub renderer: Box<dyn BoardRenderer>, //How the frontend draws the boards
+    pub seed: u64, //Same seed, same ship layout and same AI moves
+    pub turn: Player,
+    pub history: Vec<(Player, Position, CellState)>, //Every shot fired so far, oldest first
+    pub notation: Notation,
+    pub player_stats: Stats,
+    pub computer_stats: Stats,
+    pub turn_limit: Option<Duration>, //Time to type each shot, None for no timer
+    pub debug: bool, //Allows peeking at the enemy fleet
+    pub assist: bool, //Tells the player how close a miss came, for beginners
+    pub auto_fire: bool, //Allows firing at the computer's own best guess instead of typing a cell
+    pub pause: bool, //Waits for Enter after each turn
+    pub banner: bool, //Pins the last shot to the corner of the screen
+    pub skip_turn: Option<Player>, //Who stepped on a mine and sits out their next turn
+    pub turns: usize, //Turns played by both sides together, skipped ones included
+    pub player_scans: usize, //Scans left, each fires at a whole row or column
+    pub computer_scans: usize,
+    pub player_peeks: usize, //Peeks left, each uncovers one enemy ship cell without firing
+    pub computer_peeks: usize,
+    pub max_turns: Option<usize>, //Turns each side gets before the game is called, None to play until a fleet is sunk
+    pub language: Language,
+    pub player_boards: Vec<Board>, //The team mates' boards in team games, sunk together with player_board, empty otherwise
+    pub computer_boards: Vec<Board>,
+    pub team_opponents: Vec<Box<dyn OpponentStrategy>>, //Firing at player_boards, every board needs its own targets
+    pub queued: VecDeque<Position>, //Shots typed ahead with burst, fired one at a time
+    pub burst: Option<(usize, usize)>, //Our hits and misses when the queued shots started, None without a burst
+    pub aimed: (usize, usize), //Which board of the human and the computer side sits in player_board and computer_board, 0 for the first
+    pub salvo_fired: usize, //Shots of the human's salvo already fired by step
+    pub hold_reply: bool, //The frontend plays the other side itself, step stops once our turn is over
+    pub takeback: Vec<(Stats, Option<Player>, usize)>, //Our stats, who sits out a turn and salvo_fired before each of our shots the opponent has not answered yet
+    pub swapped: bool, //Hotseat: player two is seated, see swap_sides
+    pub profile: Option<Profile>, //The human's results over every earlier game, None when they are not kept
+    pub rng: StdRng, //Anything drawn at random once the game is set up, still from the seed
+}
+
+impl Game {
+    pub fn record_shot(&mut self, player: Player, position: Position, result: FireResult) {
+        let state = match result {
+            FireResult::Miss => CellState::Miss,
+            FireResult::Hit | FireResult::Sunk(_) => CellState::Hit,
+            FireResult::Mine => CellState::Detonated,
+            FireResult::AlreadyFired => return, //Wasted nothing, so there is nothing to record
+        };
+
+        self.history.push((player, position, state));
+
+        //Water uncovered around a sunk ship goes in the history right after the shot
+        let target = match player {
+            Player::Human => &self.computer_board,
+            Player::Computer => &self.player_board,
+        };
+        if let (FireResult::Sunk(_), true) = (result, target.rules.reveal_sunk) {
+            let revealed: Vec<Position> = target.ships.iter()
+                .filter(|ship| ship.cells.contains(&position))
+                .flat_map(|ship| target.keep_clear(ship))
+                .filter(|cell| target.grid[cell.row][cell.column] == CellState::Miss)
+                .filter(|&cell| !self.history.iter().any(|&(shooter, fired, _)| shooter == player && fired == cell))
+                .collect();
+            self.history.extend(revealed.into_iter().map(|cell| (player, cell, CellState::Miss)));
+        }
+    }
+
+    fn revealed_by_sinking(&self, position: Position) -> bool { //A miss nobody fired, uncovered next to a sunk ship
+        let board = &self.computer_board;
+        board.rules.reveal_sunk && board.ships.iter().any(|ship| ship.is_sunk(board) && board.keep_clear(ship).contains(&position))
+    }
+
+    pub fn undo_last_shot(&mut self) -> Option<Position> { //Take back our latest shot while the opponent has not answered it, the turn is ours again
+        let (stats, skip_turn, salvo_fired) = self.takeback.pop()?;
+        let shooter = self.history.last().expect("a shot to take back is in the history").0;
+
+        //Water revealed by a sinking shot goes back together with the shot
+        while let Some(&(_, position, CellState::Miss)) = self.history.last() {
+            if !self.revealed_by_sinking(position) {
+                break;
+            }
+            self.history.pop();
+            self.computer_board.undo_fire(position).expect("the history only holds cells of the board");
+        }
+
+        let (_, position, _) = self.history.pop().expect("a shot to take back is in the history");
+        self.computer_board.undo_fire(position).expect("the history only holds cells of the board");
+        if self.turn != shooter { //The turn was over, but the reply was held back
+            self.turn = shooter;
+            self.turns -= 1;
+        }
+        (self.player_stats, self.skip_turn, self.salvo_fired) = (stats, skip_turn, salvo_fired); //Score, streak and the turn count as before the shot
+
+        Some(position)
+    }
+
+    pub fn format_shot(&self, &(player, position, state): &(Player, Position, CellState)) -> String {
+        let text = self.language.messages();
+        let result = match state {
+            CellState::Hit => text.shot_hit,
+            CellState::Detonated => text.shot_mine,
+            _ => text.shot_miss,
+        };
+        let player = match (self.mode, player) {
+            (GameMode::Hotseat, Player::Human) => "P1",
+            (GameMode::Hotseat, Player::Computer) => "P2",
+            (GameMode::Network, Player::Computer) => "O",
+            (_, Player::Human) => "P",
+            (_, Player::Computer) => "C",
+        };
+
+        format!("{} {} {}", player, self.notation.format(position), result)
+    }
+
+    pub fn player_label(&self, player: Player) -> String {
+        let text = self.language.messages();
+        match (self.mode, player) {
+            (GameMode::Hotseat, Player::Human) => text.player_one,
+            (GameMode::Hotseat, Player::Computer) => text.player_two,
+            (_, Player::Human) => text.you,
+            (_, Player::Computer) => text.opponent,
+        }.to_string()
+    }
+
+    pub fn auto_target(&mut self, salvo: &[Position]) -> Option<Position> { //One of the best guesses at computer_board, cells already in the salvo count as fired at, None once every cell is
+        let mut board = self.computer_board.clone();
+        for &position in salvo {
+            board.set_cell(position, CellState::Miss);
+        }
+        let best = best_guesses(&board);
+        (!best.is_empty()).then(|| best[self.rng.gen_range(0..best.len())])
+    }
+
+    pub fn peek(&mut self, shooter: Player) -> Option<Position> { //Spend one of the shooter's peeks on the enemy board they aim at
+        let (peeks, target) = match shooter {
+            Player::Human => (&mut self.player_peeks, &mut self.computer_board),
+            Player::Computer => (&mut self.computer_peeks, &mut self.player_board),
+        };
+        if *peeks == 0 {
+            return None;
+        }
+
+        let position = target.peek(&mut self.rng)?; //From the seed, so a seeded game peeks at the same cells every time
+        *peeks -= 1;
+        Some(position)
+    }
+
+    pub fn computer_salvo(&mut self, shots: usize) -> Vec<Position> { //A peek leads the salvo when the strategy has no wounded ship to chase
+        let state = self.opponent.state();
+        let idle = state.targets.is_empty() && state.last_hit.is_none();
+        let peeked = if idle && shots > 0 { self.peek(Player::Computer) } else { None };
+
+        let mut board = self.player_board.clone();
+        let mut salvo: Vec<Position> = peeked.into_iter().collect();
+        for position in &salvo {
+            board.grid[position.row][position.column] = CellState::Miss; //So the strategy does not pick it again
+        }
+        salvo.extend(opponent_salvo(self.opponent.as_mut(), &board, shots - salvo.len()));
+        salvo
+    }
+
+    pub fn player_fire(&mut self, shooter: Player, target: Position) -> Result<(FireResult, Option<usize>), OutOfBounds> { //One shot of whoever sits at player_board, also how close a miss came
+        let before = (self.player_stats.clone(), self.skip_turn, self.salvo_fired);
+        let (result, distance) = self.computer_board.fire_assisted(target)?;
+        if result != FireResult::AlreadyFired { //Nothing to take back otherwise
+            self.takeback.push(before);
+        }
+        self.record_shot(shooter, target, result);
+        self.player_stats.record(result);
+        if result == FireResult::Mine {
+            self.skip_turn = Some(shooter);
+        }
+        Ok((result, distance))
+    }
+
+    pub fn computer_turn(&mut self) -> Vec<(Position, FireResult)> { //The computer's salvo at the human side, stops once the board it aims at is sunk
+        self.takeback.clear(); //Answered, too late to take our shots back
+        self.computer_stats.turns += 1;
+        if self.mode == GameMode::Teams {
+            let board = self.team_target();
+            self.aim_at(Player::Human, board);
+        }
+
+        let shots = self.mode.shots(&self.computer_board);
+        let mut fired = Vec::with_capacity(shots);
+        for position in self.computer_salvo(shots) {
+            let result = self.player_board.fire(position).expect("strategies only pick cells of the board");
+            self.opponent.record(position, result);
+            self.record_shot(Player::Computer, position, result);
+            self.computer_stats.record(result);
+            if result == FireResult::Mine {
+                self.skip_turn = Some(Player::Computer);
+            }
+            fired.push((position, result));
+
+            if self.player_board.game_over() {
+                break;
+            }
+        }
+        fired
+    }
+
+    pub fn step(&mut self, action: InputAction) -> TurnOutcome { //One action of the human against the computer and whatever the computer does before it is our turn again
+        let mut outcome = TurnOutcome::new(self.turn);
+        self.play(action, &mut outcome);
+        outcome.turn = self.turn;
+        outcome
+    }
+
+    fn play(&mut self, action: InputAction, outcome: &mut TurnOutcome) {
+        if action == InputAction::Undo {
+            outcome.undone = self.undo_last_shot();
+            return;
+        }
+        for side in [Player::Human, Player::Computer] {
+            if self.all_sunk(side) { //Nothing left to step through
+                outcome.over = true;
+                outcome.winner = Some(match side {
+                    Player::Human => Player::Computer,
+                    Player::Computer => Player::Human,
+                });
+                return;
+            }
+        }
+        if self.salvo_fired == 0 && !self.hold_reply { //A turn of ours starts, unless the computer or a mine has it
+            self.respond(outcome);
+            if outcome.over {
+                return;
+            }
+        }
+
+        let action = match action {
+            InputAction::Auto => self.auto_target(&[]).map_or(InputAction::Auto, InputAction::Fire),
+            action => action,
+        };
+        let shooter = self.turn; //The human, or in hotseat games whoever is seated
+        match action {
+            InputAction::Fire(position) | InputAction::Queued(position) => {
+                let (result, distance) = match self.player_fire(shooter, position) {
+                    Ok(fired) => fired,
+                    Err(e) => {
+                        outcome.off_board = Some(e); //Nothing else happens
+                        return;
+                    }
+                };
+                outcome.shots.push((shooter, position, result));
+                outcome.distance = distance;
+                self.salvo_fired += 1;
+
+                let sunk = self.all_sunk(Player::Computer);
+                if !sunk && self.mode.fires_again(result) {
+                    self.salvo_fired = 0;
+                    return; //Fires once more in the same turn
+                }
+                if !sunk && self.salvo_fired < self.mode.shots(&self.player_board) {
+                    return; //The rest of the salvo comes with the next steps
+                }
+            }
+            InputAction::Scan(_, _) if self.player_scans == 0 || self.salvo_fired > 0 => return, //None left, or the salvo has started
+            InputAction::Scan(axis, index) => {
+                self.player_scans -= 1;
+                outcome.scanned = Some(self.scan(shooter, axis, index));
+            }
+            InputAction::TimeUp => (), //Whatever was fired of the salvo stands
+            InputAction::Auto | InputAction::Undo => return, //Every cell was fired at already, or taken back above
+            InputAction::Quit => {
+                outcome.over = true;
+                return;
+            }
+            InputAction::Surrender => {
+                outcome.over = true;
+                outcome.winner = Some(Player::Computer);
+                return;
+            }
+        }
+
+        self.salvo_fired = 0;
+        self.player_stats.turns += 1;
+        if self.all_sunk(Player::Computer) {
+            outcome.over = true;
+            outcome.winner = Some(Player::Human);
+            return;
+        }
+        self.pass_turn();
+        if !self.hold_reply {
+            self.respond(outcome);
+        }
+    }
+
+    fn respond(&mut self, outcome: &mut TurnOutcome) { //Turns of the computer and skipped ones, until ours is due or the game is over
+        loop {
+            if self.out_of_turns() {
+                outcome.over = true;
+                outcome.winner = self.leader();
+                return;
+            }
+            if self.skip_mined_turn() {
+                continue;
+            }
+            if self.turn == Player::Human {
+                return;
+            }
+
+            let (fired, _) = self.computer_reply();
+            outcome.shots.extend(fired.into_iter().map(|(position, result)| (Player::Computer, position, result)));
+            if self.all_sunk(Player::Human) {
+                outcome.over = true;
+                outcome.winner = Some(Player::Computer);
+                return;
+            }
+        }
+    }
+
+    pub fn pass_turn(&mut self) { //The other side is up, the turn counts towards the limit
+        self.turn = match self.turn {
+            Player::Human => Player::Computer,
+            Player::Computer => Player::Human,
+        };
+        self.turns += 1;
+    }
+
+    pub fn skip_mined_turn(&mut self) -> bool { //Passes the turn of a side that set off a mine last turn, true if it did
+        if self.skip_turn != Some(self.turn) {
+            return false;
+        }
+        self.skip_turn = None;
+        self.takeback.clear(); //Our next turn has come, the last one stands
+        self.pass_turn();
+        true
+    }
+
+    pub fn computer_reply(&mut self) -> (Vec<(Position, FireResult)>, bool) { //The computer's turn against the human, also whether it fires again, the turn is passed on otherwise
+        let fired = self.computer_turn();
+        if self.all_sunk(Player::Human) {
+            return (fired, false);
+        }
+        let chained = fired.last().is_some_and(|&(_, result)| self.mode.fires_again(result));
+        if chained { //Keeps firing until it misses, all in the same turn
+            self.computer_stats.turns -= 1;
+        } else {
+            self.pass_turn();
+        }
+        (fired, chained)
+    }
+
+    pub fn scan(&mut self, shooter: Player, axis: Axis, index: usize) -> usize { //Scan a line of computer_board, each cell it fires at is kept like a shot so replays and stats see it, returns the hits
+        let fired = self.computer_board.scan_line(axis, index);
+        self.takeback.clear(); //A scan cannot be taken back, nor anything before it
+        for &(position, result) in &fired {
+            self.record_shot(shooter, position, result);
+            self.player_stats.record(result);
+        }
+        fired.iter().filter(|(_, result)| matches!(result, FireResult::Hit | FireResult::Sunk(_))).count()
+    }
+
+    pub fn swap_sides(&mut self) { //Hotseat: the other human takes the seat, their fleet becomes "your ships"
+        std::mem::swap(&mut self.player_board, &mut self.computer_board);
+        std::mem::swap(&mut self.player_stats, &mut self.computer_stats);
+        std::mem::swap(&mut self.player_scans, &mut self.computer_scans);
+        std::mem::swap(&mut self.player_peeks, &mut self.computer_peeks);
+        self.swapped = !self.swapped;
+        self.takeback.clear(); //The shots were the other player's
+
+        self.player_board.board_visibility = BoardVisibility::Visible;
+        self.computer_board.board_visibility = BoardVisibility::Hidden;
+    }
+
+    pub fn aim_at(&mut self, side: Player, board: usize) { //Swap that board of the side in as player_board or computer_board
+        let aimed = match side {
+            Player::Human => &mut self.aimed.0,
+            Player::Computer => &mut self.aimed.1,
+        };
+        let previous = std::mem::replace(aimed, board);
+
+        //Back to the first board, then on to the chosen one
+        for index in [previous, board].into_iter().filter(|&index| index > 0) {
+            match side {
+                Player::Human => {
+                    std::mem::swap(&mut self.player_board, &mut self.player_boards[index - 1]);
+                    std::mem::swap(&mut self.opponent, &mut self.team_opponents[index - 1]);
+                }
+                Player::Computer => std::mem::swap(&mut self.computer_board, &mut self.computer_boards[index - 1]),
+            }
+        }
+    }
+
+    pub fn boards(&self, side: Player) -> Vec<&Board> { //Every board of the side in their own order, whichever one is aimed at
+        let (first, rest, aimed) = match side {
+            Player::Human => (&self.player_board, &self.player_boards, self.aimed.0),
+            Player::Computer => (&self.computer_board, &self.computer_boards, self.aimed.1),
+        };
+        let mut boards: Vec<&Board> = std::iter::once(first).chain(rest).collect();
+        boards.swap(0, aimed);
+        boards
+    }
+
+    pub fn boards_mut(&mut self) -> impl Iterator<Item = &mut Board> { //Both sides, e.g. to change how every board is drawn
+        std::iter::once(&mut self.player_board).chain(&mut self.player_boards)
+            .chain(std::iter::once(&mut self.computer_board)).chain(&mut self.computer_boards)
+    }
+
+    pub fn all_sunk(&self, side: Player) -> bool { //Lost, in team games only once every board of the side is
+        self.boards(side).iter().all(|board| board.game_over())
+    }
+
+    pub fn afloat(&self, side: Player) -> (usize, usize) { //Ships of the side still afloat, and how many it had
+        let boards = self.boards(side);
+        (boards.iter().map(|board| board.surviving_ships()).sum(), boards.iter().map(|board| board.ships.len()).sum())
+    }
+
+    pub fn team_target(&self) -> usize { //The human board the computer fires at next
+        let boards = self.boards(Player::Human);
+        let afloat = (0..boards.len()).filter(|&index| !boards[index].game_over());
+        let wounded = |board: &Board| board.ships.iter().any(|ship| {
+            let (hits, size) = ship.damage(board);
+            hits > 0 && hits < size
+        });
+        let fired = |board: &Board| board.rows * board.columns - board.unfired_positions().len();
+
+        //Finish off a ship that was hit, or else search the board that has seen the fewest shots
+        afloat.clone().find(|&index| wounded(boards[index]))
+            .or_else(|| afloat.min_by_key(|&index| fired(boards[index])))
+            .unwrap_or(0)
+    }
+
+    pub fn out_of_turns(&self) -> bool {
+        self.max_turns.is_some_and(|max| self.turns >= max * 2)
+    }
+
+    pub fn leader(&self) -> Option<Player> { //Whoever has more of their fleet afloat, None for a draw
+        let afloat = |side: Player| self.boards(side).iter().map(|board| board.remaining_ship_cells()).sum::<usize>();
+        let (own, enemy) = (afloat(Player::Human), afloat(Player::Computer));
+        match own.cmp(&enemy) {
+            Ordering::Greater => Some(Player::Human),
+            Ordering::Less => Some(Player::Computer),
+            Ordering::Equal => None,
+        }
+    }
+
+    pub fn play_out(&mut self, challenger: &mut dyn OpponentStrategy) -> Option<Player> { //The challenger fires for the human side, returns the winner or None if the shots ran out or it is a draw
+        let mut outcome = TurnOutcome::new(self.turn);
+        self.respond(&mut outcome); //The computer may have the first turn
+        while !outcome.over {
+            let salvo = opponent_salvo(challenger, &self.computer_board, self.mode.shots(&self.player_board));
+            if salvo.is_empty() {
+                return None;
+            }
+
+            for position in salvo {
+                outcome = self.step(InputAction::Fire(position));
+                if outcome.off_board.is_some() {
+                    return None; //A strategy off the board cannot finish the game
+                }
+                if let Some(&(_, _, result)) = outcome.shots.first().filter(|&&(player, _, _)| player == Player::Human) {
+                    challenger.record(position, result);
+                }
+                if outcome.over || self.salvo_fired == 0 { //A chained hit picks its next shot from the board as it is now
+                    break;
+                }
+            }
+        }
+        outcome.winner
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame { //Everything needed to resume a game, as written to the save file
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub turn: Player,
+    pub player_board: Board,
+    pub computer_board: Board,
+    pub ai_state: AiState,
+    #[serde(default)]
+    pub history: Vec<(Player, Position, CellState)>,
+    #[serde(default)]
+    pub skip_turn: Option<Player>,
+    #[serde(default)]
+    pub turns: usize,
+    #[serde(default)]
+    pub scans: (usize, usize), //Left for the player and the computer
+    #[serde(default)]
+    pub peeks: (usize, usize),
+    #[serde(default)]
+    pub stats: (Stats, Stats), //The player's and the computer's so far, without the streak and think times
+    #[serde(default)]
+    pub max_turns: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    GridSize, //The grid does not have the size the board claims
+    ShipOffBoard(ShipKind),
+    ShipMismatch(ShipKind), //The ship's cells do not match what the grid shows
+    ShipOverlap(ShipKind), //Shares a cell with a ship listed before it
+    StrayShipCell(Position), //The grid shows a ship where no ship is listed
+    ShotOffBoard(Position), //The history has a shot outside the board
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (Language::English.messages().load_error)(self))
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+pub fn save_game(game: &Game, path: &str) -> io::Result<()> {
+    //Saved with player one seated, as a loaded game starts out
+    let (mut player_board, mut computer_board) = (game.player_board.clone(), game.computer_board.clone());
+    let (mut scans, mut peeks) = ((game.player_scans, game.computer_scans), (game.player_peeks, game.computer_peeks));
+    let mut stats = (game.player_stats.clone(), game.computer_stats.clone());
+    if game.swapped {
+        std::mem::swap(&mut player_board, &mut computer_board);
+        scans = (scans.1, scans.0);
+        peeks = (peeks.1, peeks.0);
+        stats = (stats.1, stats.0);
+        player_board.board_visibility = BoardVisibility::Visible;
+        computer_board.board_visibility = BoardVisibility::Hidden;
+    }
+
+    let saved = SavedGame {
+        mode: game.mode,
+        difficulty: game.difficulty,
+        seed: game.seed,
+        turn: game.turn,
+        player_board,
+        computer_board,
+        ai_state: game.opponent.state(),
+        history: game.history.clone(),
+        skip_turn: game.skip_turn,
+        turns: game.turns,
+        scans,
+        peeks,
+        stats,
+        max_turns: game.max_turns,
+    };
+
+    let json = serde_json::to_string_pretty(&saved).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+#[derive(Serialize)]
+struct SideReport<'a> {
+    #[serde(flatten)]
+    stats: &'a Stats,
+    accuracy: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think_time: Option<ThinkReport>,
+}
+
+#[derive(Serialize)]
+struct ThinkReport {
+    average_ms: u64,
+    fastest_ms: u64,
+    slowest_ms: u64,
+}
+
+#[derive(Serialize)]
+struct StatsReport<'a> { //The end of a game as written for --stats-out
+    seed: u64,
+    winner: Option<Player>, //None for a draw
+    turns: usize,
+    player: SideReport<'a>,
+    computer: SideReport<'a>,
+}
+
+pub fn save_stats(game: &Game, winner: Option<Player>, path: &str) -> io::Result<()> {
+    let side = |stats| SideReport {
+        stats,
+        accuracy: stats.accuracy(),
+        think_time: stats.think_time().map(|time| ThinkReport {
+            average_ms: time.average.as_millis() as u64,
+            fastest_ms: time.fastest.as_millis() as u64,
+            slowest_ms: time.slowest.as_millis() as u64,
+        }),
+    };
+    let report = StatsReport {
+        seed: game.seed,
+        winner,
+        turns: game.turns,
+        player: side(&game.player_stats),
+        computer: side(&game.computer_stats),
+    };
+
+    let json = serde_json::to_string_pretty(&report).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+#[derive(Copy, Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Profile { //Lifetime results of the human side, kept in a file from one run to the next
+    pub played: usize,
+    pub won: usize,
+    pub lost: usize, //Draws are the rest of played
+    pub shots: usize,
+    pub hits: usize,
+}
+
+impl Profile {
+    pub fn add(&mut self, winner: Option<Player>, stats: &Stats) { //One finished game, played from the human side
+        self.played += 1;
+        match winner {
+            Some(Player::Human) => self.won += 1,
+            Some(Player::Computer) => self.lost += 1,
+            None => (),
+        }
+        self.shots += stats.shots;
+        self.hits += stats.hits;
+    }
+
+    pub fn accuracy(&self) -> f64 { //Percentage of every shot ever fired that hit
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / self.shots as f64
+        }
+    }
+}
+
+pub fn read_profile(path: &str) -> io::Result<Profile> { //A file that is not there yet is a fresh record
+    match fs::read_to_string(path) {
+        Ok(json) => serde_json::from_str(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Profile::default()),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn save_profile(profile: &Profile, path: &str) -> io::Result<()> {
+    if let Some(directory) = std::path::Path::new(path).parent() {
+        fs::create_dir_all(directory)?; //The config directory may not have our folder yet
+    }
+    let json = serde_json::to_string_pretty(profile).map_err(io::Error::other)?;
+    fs::write(path, json)
+}
+
+pub fn read_save(path: &str) -> Result<SavedGame, LoadError> { //A save file, checked but not yet turned into a game
+    let json = fs::read_to_string(path).map_err(LoadError::Io)?;
+    let mut saved: SavedGame = serde_json::from_str(&json).map_err(LoadError::Format)?;
+
+    for board in [&mut saved.player_board, &mut saved.computer_board] {
+        if board.columns == 0 { //Saved before boards could be rectangular
+            board.columns = board.rows;
+        }
+        board.validate()?;
+    }
+    if (saved.player_board.rows, saved.player_board.columns) != (saved.computer_board.rows, saved.computer_board.columns) {
+        return Err(LoadError::GridSize);
+    }
+    if let Some(&(_, position, _)) = saved.history.iter().find(|(_, position, _)| !saved.player_board.contains(*position)) {
+        return Err(LoadError::ShotOffBoard(position));
+    }
+
+    Ok(saved)
+}
+
+pub fn load_game(path: &str) -> Result<Game, LoadError> {
+    let saved = read_save(path)?;
+
+    Ok(Game {
+        mode: saved.mode,
+        difficulty: saved.difficulty,
+        opponent: saved.difficulty.strategy(StdRng::seed_from_u64(saved.seed), saved.ai_state),
+        renderer: Box::new(TerminalRenderer),
+        player_board: saved.player_board,
+        computer_board: saved.computer_board,
+        seed: saved.seed,
+        turn: saved.turn,
+        history: saved.history,
+        notation: Notation::Numeric,
+        player_stats: saved.stats.0,
+        computer_stats: saved.stats.1,
+        turn_limit: None,
+        debug: false,
+        assist: false,
+        auto_fire: false,
+        pause: true,
+        banner: false,
+        skip_turn: saved.skip_turn,
+        turns: saved.turns,
+        player_scans: saved.scans.0,
+        computer_scans: saved.scans.1,
+        player_peeks: saved.peeks.0,
+        computer_peeks: saved.peeks.1,
+        max_turns: saved.max_turns,
+        language: Language::default(),
+        player_boards: Vec::new(), //Team games are not saved
+        computer_boards: Vec::new(),
+        team_opponents: Vec::new(),
+        queued: VecDeque::new(),
+        burst: None,
+        aimed: (0, 0),
+        salvo_fired: 0,
+        hold_reply: false,
+        takeback: Vec::new(),
+        swapped: false,
+        profile: None,
+        rng: StdRng::seed_from_u64(saved.seed ^ saved.turns as u64),
+    })
+}
+
+impl Game {
+    pub fn new(board_size: (usize, usize), seed: u64, mode: GameMode, difficulty: Difficulty, rules: Rules, fleet: &Fleet, player_placement: PlayerPlacement) -> Result<Self, FleetError> {
+        Game::with_fleets(board_size, seed, mode, difficulty, rules, (fleet, fleet), player_placement)
+    }
+
+    pub fn with_fleets((rows, columns): (usize, usize), seed: u64, mode: GameMode, difficulty: Difficulty, rules: Rules, (player_fleet, computer_fleet): (&Fleet, &Fleet), player_placement: PlayerPlacement) -> Result<Self, FleetError> { //Each side gets its own fleet, e.g. an extra ship as a handicap
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut player_board = Board::new(rows, columns, BoardVisibility::Visible, rules);
+        let mut computer_board = Board::new(rows, columns, BoardVisibility::Hidden, rules);
+
+        match player_placement {
+            PlayerPlacement::Random => player_board.try_place_fleet(player_fleet, &mut rng)?,
+            PlayerPlacement::Manual => check_fleet(player_fleet, rows, columns)?, //Before anyone starts placing it
+        }
+        let own_fleet = match mode { //Otherwise the other side places their fleet themselves
+            GameMode::Classic | GameMode::Salvo | GameMode::Teams | GameMode::Chain => true,
+            GameMode::Hotseat => matches!(player_placement, PlayerPlacement::Random),
+            GameMode::Network => false, //Only a record of our shots, the fleet lives in the other process
+        };
+        if own_fleet && mode != GameMode::Hotseat && difficulty == Difficulty::Hard && rules.placement == PlacementStrategy::Random {
+            computer_board.rules.placement = PlacementStrategy::Interior; //A harder computer also hides its fleet better
+        }
+        if own_fleet {
+            computer_board.try_place_fleet(computer_fleet, &mut rng)?;
+        } else {
+            check_fleet(computer_fleet, rows, columns)?;
+        }
+
+        //Mines go into the water that is left, so only once the fleet is in place
+        if let PlayerPlacement::Random = player_placement {
+            player_board.place_mines(rules.mines, &mut rng);
+        }
+        if own_fleet {
+            computer_board.place_mines(rules.mines, &mut rng);
+        }
+
+        //Team mates come last, so the other modes lay out the same fleets for a seed
+        let (mut player_boards, mut computer_boards, mut team_opponents) = (Vec::new(), Vec::new(), Vec::new());
+        if mode == GameMode::Teams {
+            for _ in 1..TEAM_BOARDS {
+                for (boards, visibility, rules, fleet) in [(&mut player_boards, BoardVisibility::Visible, rules, player_fleet), (&mut computer_boards, BoardVisibility::Hidden, computer_board.rules, computer_fleet)] {
+                    let mut board = Board::new(rows, columns, visibility, rules);
+                    board.try_place_fleet(fleet, &mut rng)?;
+                    board.place_mines(rules.mines, &mut rng);
+                    boards.push(board);
+                }
+                team_opponents.push(difficulty.strategy(StdRng::seed_from_u64(rng.gen()), AiState::default()));
+            }
+        }
+
+        Ok(Game {
+            mode,
+            difficulty,
+            player_board,
+            computer_board,
+            opponent: difficulty.strategy(StdRng::seed_from_u64(rng.gen()), AiState::default()),
+            renderer: Box::new(TerminalRenderer),
+            seed,
+            turn: Player::Human,
+            history: Vec::new(),
+            notation: Notation::Numeric,
+            player_stats: Stats::default(),
+            computer_stats: Stats::default(),
+            turn_limit: None,
+            debug: false,
+            assist: false,
+            auto_fire: false,
+            pause: true,
+            banner: false,
+            skip_turn: None,
+            turns: 0,
+            player_scans: 0,
+            computer_scans: 0,
+            player_peeks: 0,
+            computer_peeks: 0,
+            max_turns: None,
+            language: Language::default(),
+            player_boards,
+            computer_boards,
+            team_opponents,
+            queued: VecDeque::new(),
+            burst: None,
+            aimed: (0, 0),
+            salvo_fired: 0,
+            hold_reply: false,
+            takeback: Vec::new(),
+            swapped: false,
+            profile: None,
+            rng: StdRng::seed_from_u64(rng.gen()), //Drawn last, so the layouts of a seed stay the same
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules::default())
+    }
+
+    fn classic_game(seed: u64) -> Game {
+        Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), seed, GameMode::Classic, Difficulty::Easy, Rules::default(), &CLASSIC_FLEET.to_vec(), PlayerPlacement::Random).unwrap()
+    }
+
+    struct TempFile(String); //Removed again when the test is done, pass or fail
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn temp_file(name: &str) -> TempFile {
+        let path = std::env::temp_dir().join(format!("battleship_{}_{}.json", name, std::process::id()));
+        TempFile(path.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn horizontal_ship_fits_against_right_edge() {
+        let mut board = board();
+        let position = Position { row: 3, column: DEFAULT_BOARD_SIZE - 4 };
+
+        assert!(board.can_place(&position, 4, Orientation::Horizontal));
+        assert!(board.place_ship_at(ShipKind::Battleship, position, 4, Orientation::Horizontal).is_ok());
+        assert!(board.grid[3][DEFAULT_BOARD_SIZE - 1] == CellState::Ship);
+    }
+
+    #[test]
+    fn horizontal_ship_one_cell_over_right_edge_is_rejected() {
+        let board = board();
+        let position = Position { row: 3, column: DEFAULT_BOARD_SIZE - 3 };
+
+        assert!(!board.can_place(&position, 4, Orientation::Horizontal));
+        assert!(matches!(board.check_placement(&position, 4, Orientation::Horizontal), Err(PlacementError::OffBoard)));
+    }
+
+    #[test]
+    fn vertical_ship_fits_against_bottom_edge() {
+        let mut board = board();
+        let position = Position { row: DEFAULT_BOARD_SIZE - 5, column: 0 };
+
+        assert!(board.can_place(&position, 5, Orientation::Vertical));
+        assert!(board.place_ship_at(ShipKind::Carrier, position, 5, Orientation::Vertical).is_ok());
+        assert!(board.grid[DEFAULT_BOARD_SIZE - 1][0] == CellState::Ship);
+    }
+
+    #[test]
+    fn vertical_ship_one_cell_over_bottom_edge_is_rejected() {
+        let board = board();
+        let position = Position { row: DEFAULT_BOARD_SIZE - 4, column: 0 };
+
+        assert!(!board.can_place(&position, 5, Orientation::Vertical));
+        assert!(matches!(board.check_placement(&position, 5, Orientation::Vertical), Err(PlacementError::OffBoard)));
+    }
+
+    #[test]
+    fn start_off_the_board_is_rejected() {
+        let board = board();
+
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            assert!(!board.can_place(&Position { row: DEFAULT_BOARD_SIZE, column: 0 }, 2, orientation));
+            assert!(!board.can_place(&Position { row: 0, column: DEFAULT_BOARD_SIZE }, 2, orientation));
+        }
+    }
+
+    #[test]
+    fn overlapping_ships_are_rejected() {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Cruiser, Position { row: 4, column: 2 }, 3, Orientation::Horizontal).unwrap();
+
+        //Crossing the middle of the cruiser from above, and running into its end from the left
+        assert!(matches!(
+            board.place_ship_at(ShipKind::Submarine, Position { row: 2, column: 3 }, 3, Orientation::Vertical),
+            Err(PlacementError::Overlap)
+        ));
+        assert!(matches!(
+            board.place_ship_at(ShipKind::Destroyer, Position { row: 4, column: 0 }, 3, Orientation::Horizontal),
+            Err(PlacementError::Overlap)
+        ));
+        assert_eq!(board.ships.len(), 1);
+    }
+
+    #[test]
+    fn ascii_board_uses_plain_characters() {
+        let mut board = Board::new(5, 6, BoardVisibility::Visible, Rules::default());
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 1, column: 1 }, 2, Orientation::Horizontal).unwrap();
+        board.fire(Position { row: 1, column: 2 }).unwrap();
+        board.fire(Position { row: 3, column: 0 }).unwrap();
+
+        assert_eq!(board.to_ascii(), concat!(
+            "   0  1  2  3  4  5 \n",
+            "0  .  .  .  .  .  . \n",
+            "1  .  #  X  .  .  . \n",
+            "2  .  .  .  .  .  . \n",
+            "3  o  .  .  .  .  . \n",
+            "4  .  .  .  .  .  . \n",
+        ));
+
+        board.board_visibility = BoardVisibility::Hidden;
+        assert!(board.to_ascii().lines().nth(2) == Some("1  .  .  X  .  .  . "));
+    }
+
+    #[test]
+    fn valid_placements_on_an_empty_board() {
+        //Each orientation fits 8 starts along a line of 10, on each of the 10 lines
+        assert_eq!(board().valid_placements(3).len(), 2 * 10 * 8);
+        assert_eq!(board().valid_placements(DEFAULT_BOARD_SIZE + 1).len(), 0);
+
+        let diagonal = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules { diagonal: true, ..Rules::default() });
+        assert_eq!(diagonal.valid_placements(3).len(), 2 * 10 * 8 + 2 * 8 * 8);
+
+        let wide = Board::new(4, 6, BoardVisibility::Visible, Rules::default());
+        assert_eq!(wide.valid_placements(5).len(), 4 * 2);
+    }
+
+    #[test]
+    fn valid_placements_respect_no_touch() {
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules { adjacency: AdjacencyRule::NoTouch, ..Rules::default() });
+        board.place_ship_at(ShipKind::Carrier, Position { row: 0, column: 0 }, 5, Orientation::Horizontal).unwrap();
+        let before = board.grid.clone();
+
+        //Rows 0 and 1 are ruled out from column 0 to 5, which leaves 3 horizontal starts on each of them
+        let placements = board.valid_placements(2);
+        let horizontal = placements.iter().filter(|(_, orientation)| matches!(orientation, Orientation::Horizontal)).count();
+        assert_eq!(horizontal, 8 * 9 + 2 * 3);
+        assert!(placements.iter().all(|(position, orientation)| board.can_place(position, 2, *orientation)));
+        assert!(board.grid == before);
+    }
+
+    #[test]
+    fn heatmap_counts_placements_over_unfired_cells() {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 5, column: 5 }, 2, Orientation::Horizontal).unwrap();
+
+        let heat = board.heatmap();
+        assert_eq!((heat[0][0], heat[0][1], heat[4][4]), (2, 3, 4)); //Where the ship is makes no difference while it is hidden
+
+        board.fire(Position { row: 0, column: 1 }).unwrap();
+        let heat = board.heatmap();
+        assert_eq!((heat[0][0], heat[0][1]), (1, 0));
+    }
+
+    #[test]
+    fn interior_placement_keeps_off_the_edge_while_it_can() {
+        let rules = Rules { placement: PlacementStrategy::Interior, ..Rules::default() };
+        let on_edge = |board: &Board| board.ships.iter().flat_map(|ship| &ship.cells)
+            .any(|cell| cell.row == 0 || cell.column == 0 || cell.row + 1 == board.rows || cell.column + 1 == board.columns);
+
+        for seed in 0..20 {
+            let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, rules);
+            board.try_place_fleet(&CLASSIC_FLEET.to_vec(), &mut StdRng::seed_from_u64(seed)).unwrap();
+            assert!(!on_edge(&board));
+        }
+
+        //Only a single cell is inside a 3x3 board, so the ship has to go on the edge
+        let mut small = Board::new(3, 3, BoardVisibility::Visible, rules);
+        small.try_place_fleet(&vec![(ShipKind::Submarine, 3)], &mut StdRng::seed_from_u64(1)).unwrap();
+        assert!(on_edge(&small));
+
+        let game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 1, GameMode::Classic, Difficulty::Hard, Rules::default(), &CLASSIC_FLEET.to_vec(), PlayerPlacement::Random).unwrap();
+        assert!(game.computer_board.rules.placement == PlacementStrategy::Interior && !on_edge(&game.computer_board));
+        assert!(game.player_board.rules.placement == PlacementStrategy::Random);
+    }
+
+    #[test]
+    fn peeks_uncover_ship_cells_without_hitting_them() {
+        let mut game = classic_game(3);
+        assert_eq!(game.peek(Player::Human), None); //No charges to spend
+
+        game.player_peeks = 1;
+        let position = game.peek(Player::Human).unwrap();
+        assert_eq!(game.computer_board.cell(position), Some(CellState::Ship));
+        assert_eq!(game.computer_board.peeked, vec![position]);
+        assert_eq!(game.player_peeks, 0);
+        assert!(game.history.is_empty());
+
+        //The computer fires at what it peeked at first, and only when it has nothing to chase
+        game.computer_peeks = 2;
+        let salvo = game.computer_salvo(2);
+        assert_eq!(salvo[0], game.player_board.peeked[0]);
+        assert_ne!(salvo[1], salvo[0]);
+        assert_eq!(game.computer_peeks, 1);
+    }
+
+    #[test]
+    fn ships_are_laid_out_from_an_anchor_either_way() {
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules { diagonal: true, ..Rules::default() });
+        let cells = board.place_ship_towards(ShipKind::Submarine, Position { row: 4, column: 4 }, 3, Orientation::Horizontal, Extend::Backward, false).unwrap();
+        assert_eq!(cells, vec![Position { row: 4, column: 2 }, Position { row: 4, column: 3 }, Position { row: 4, column: 4 }]);
+
+        //Running off the bottom only works when it may go the other way
+        let anchor = Position { row: 8, column: 0 };
+        assert!(matches!(board.place_ship_towards(ShipKind::Battleship, anchor, 4, Orientation::Vertical, Extend::Forward, false), Err(PlacementError::OffBoard)));
+        let cells = board.place_ship_towards(ShipKind::Battleship, anchor, 4, Orientation::Vertical, Extend::Forward, true).unwrap();
+        assert_eq!(cells.first(), Some(&Position { row: 5, column: 0 }));
+        assert_eq!(cells.last(), Some(&anchor));
+
+        let cells = board.place_ship_towards(ShipKind::Destroyer, Position { row: 0, column: 9 }, 2, Orientation::DiagonalUp, Extend::Backward, false).unwrap();
+        assert_eq!(cells, vec![Position { row: 1, column: 8 }, Position { row: 0, column: 9 }]);
+
+        //Flipping does not get around other ships
+        assert!(matches!(board.place_ship_towards(ShipKind::Carrier, Position { row: 4, column: 6 }, 5, Orientation::Horizontal, Extend::Backward, true), Err(PlacementError::Overlap)));
+    }
+
+    #[test]
+    fn renderers_can_be_swapped() {
+        struct Hits; //Just how many shots hit, as another frontend might want it
+        impl BoardRenderer for Hits {
+            fn render(&self, board: &Board) -> String {
+                board.count(CellState::Hit).to_string()
+            }
+        }
+
+        let mut game = classic_game(4);
+        let ship = game.computer_board.ships[0].cells[0];
+        game.computer_board.fire(ship).unwrap();
+
+        assert_eq!(game.renderer.render(&game.computer_board), game.computer_board.to_string());
+        assert_eq!(AsciiRenderer.render(&game.computer_board), game.computer_board.to_ascii());
+        game.renderer = Box::new(Hits);
+        assert_eq!(game.renderer.render(&game.computer_board), "1");
+    }
+
+    #[test]
+    fn crowded_fleets_fail_fast_when_ships_may_not_touch() {
+        let fleet = vec![(ShipKind::Battleship, 4), (ShipKind::Battleship, 4), (ShipKind::Battleship, 4)];
+        let touching = Rules { adjacency: AdjacencyRule::Touching, ..Rules::default() };
+        let mut board = Board::new(4, 4, BoardVisibility::Visible, touching);
+        board.try_place_fleet(&fleet, &mut StdRng::seed_from_u64(2)).unwrap();
+
+        let apart = Rules { adjacency: AdjacencyRule::NoTouch, ..Rules::default() };
+        let mut board = Board::new(4, 4, BoardVisibility::Visible, apart);
+        assert!(matches!(board.try_place_fleet(&fleet, &mut StdRng::seed_from_u64(2)), Err(FleetError::NoRoomToSpace { needed: 30, available: 25 })));
+        assert!(board.ships.is_empty());
+    }
+
+    #[test]
+    fn fleet_sizes_need_not_be_classic() {
+        let fleet = parse_fleet("1, 3, cruiser, 7").unwrap();
+        assert_eq!(fleet, vec![(ShipKind::Destroyer, 1), (ShipKind::Submarine, 3), (ShipKind::Cruiser, 3), (ShipKind::Carrier, 7)]);
+        assert!(check_fleet(&fleet, 8, 8).is_ok());
+        assert!(matches!(check_fleet(&fleet, 6, 6), Err(FleetError::ShipTooLong(ShipKind::Carrier))));
+        assert_eq!(parse_fleet("2, 27"), Err(FleetSpecError::NoShipOfSize(27)));
+    }
+
+    #[test]
+    fn sides_can_bring_different_fleets() {
+        let fleet = CLASSIC_FLEET.to_vec();
+        let mut stronger = fleet.clone();
+        stronger.push((ShipKind::Destroyer, 2));
+        let size = (DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE);
+
+        let game = Game::with_fleets(size, 9, GameMode::Classic, Difficulty::Easy, Rules::default(), (&fleet, &stronger), PlayerPlacement::Random).unwrap();
+        assert_eq!((game.player_board.ships.len(), game.computer_board.ships.len()), (4, 5));
+        assert_eq!(game.afloat(Player::Computer), (5, 5));
+
+        let same = Game::new(size, 9, GameMode::Classic, Difficulty::Easy, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+        assert!(game.player_board.grid == same.player_board.grid); //Our side is laid out as before
+
+        let huge = vec![(ShipKind::Carrier, 5); 30];
+        let manual = Game::with_fleets(size, 9, GameMode::Classic, Difficulty::Easy, Rules::default(), (&huge, &fleet), PlayerPlacement::Manual);
+        assert!(matches!(manual, Err(FleetError::TooManyCells { needed: 150, available: 100 }))); //Checked even when we place it ourselves
+    }
+
+    #[test]
+    fn only_hits_in_a_chain_game_fire_again() {
+        assert!(GameMode::Chain.fires_again(FireResult::Hit));
+        assert!(GameMode::Chain.fires_again(FireResult::Sunk(ShipKind::Destroyer)));
+        for result in [FireResult::Miss, FireResult::Mine, FireResult::AlreadyFired] {
+            assert!(!GameMode::Chain.fires_again(result));
+        }
+        assert!(!GameMode::Classic.fires_again(FireResult::Hit));
+        assert_eq!(GameMode::Chain.shots(&Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules::default())), 1);
+    }
+
+    #[test]
+    fn smallest_ship_afloat_follows_the_sinkings() {
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+        assert_eq!(board.min_surviving_ship_size(), None); //No fleet at all
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+        board.place_ship_at(ShipKind::Submarine, Position { row: 2, column: 0 }, 3, Orientation::Horizontal).unwrap();
+        assert_eq!(board.min_surviving_ship_size(), Some(2));
+
+        board.fire(Position { row: 0, column: 0 }).unwrap();
+        assert_eq!(board.min_surviving_ship_size(), Some(2)); //Hit, but still afloat
+        board.fire(Position { row: 0, column: 1 }).unwrap();
+        assert_eq!(board.min_surviving_ship_size(), Some(3));
+
+        for column in 0..3 {
+            board.fire(Position { row: 2, column }).unwrap();
+        }
+        assert_eq!(board.min_surviving_ship_size(), None);
+    }
+
+    #[test]
+    fn best_guesses_follow_up_on_a_hit() {
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+        board.fire(Position { row: 0, column: 0 }).unwrap();
+
+        assert_eq!(best_guesses(&board), vec![Position { row: 0, column: 1 }, Position { row: 1, column: 0 }]);
+        let mut hard = Difficulty::Hard.strategy(StdRng::seed_from_u64(1), AiState::default());
+        assert!(best_guesses(&board).contains(&hard.next_move(&board).unwrap()));
+
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+        board.place_ship_at(ShipKind::Carrier, Position { row: 6, column: 2 }, 5, Orientation::Horizontal).unwrap();
+        let peeked = board.peek(&mut StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(best_guesses(&board), vec![peeked]); //Not fired at yet, but known
+    }
+
+    #[test]
+    fn adjacency_rules_tell_corners_from_sides() {
+        let board = |adjacency: AdjacencyRule| {
+            let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules { adjacency, ..Rules::default() });
+            board.place_ship_at(ShipKind::Submarine, Position { row: 2, column: 2 }, 3, Orientation::Horizontal).unwrap();
+            board
+        };
+        let corner = Position { row: 3, column: 5 }; //Diagonal to the submarine's last cell
+        let side = Position { row: 3, column: 4 }; //Right below it
+
+        let touching = board(AdjacencyRule::Touching);
+        assert!(touching.check_placement(&corner, 2, Orientation::Vertical).is_ok());
+        assert!(touching.check_placement(&side, 2, Orientation::Vertical).is_ok());
+        assert!(touching.keep_clear(&touching.ships[0]).is_empty());
+
+        let corners = board(AdjacencyRule::Corners);
+        assert!(corners.check_placement(&corner, 2, Orientation::Vertical).is_ok());
+        assert!(matches!(corners.check_placement(&side, 2, Orientation::Vertical), Err(PlacementError::TooClose)));
+        assert_eq!(corners.keep_clear(&corners.ships[0]).len(), 8); //Above, below and at both ends
+
+        let apart = board(AdjacencyRule::NoTouch);
+        assert!(matches!(apart.check_placement(&corner, 2, Orientation::Vertical), Err(PlacementError::TooClose)));
+        assert!(matches!(apart.check_placement(&side, 2, Orientation::Vertical), Err(PlacementError::TooClose)));
+        assert_eq!(apart.keep_clear(&apart.ships[0]).len(), 12);
+
+        for board in [touching, corners, apart] {
+            assert!(matches!(board.check_placement(&Position { row: 2, column: 3 }, 2, Orientation::Vertical), Err(PlacementError::Overlap)));
+        }
+    }
+
+    #[test]
+    fn reshuffle_lays_out_a_new_fleet() {
+        let fleet = CLASSIC_FLEET.to_vec();
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Visible, Rules { mines: 3, ..Rules::default() });
+        let mut rng = StdRng::seed_from_u64(1);
+        board.try_place_fleet(&fleet, &mut rng).unwrap();
+        let before = board.grid.clone();
+
+        board.reshuffle(&fleet, &mut rng).unwrap();
+        assert!(board.grid != before);
+        assert_eq!(board.ships.len(), fleet.len());
+        assert_eq!(board.count(CellState::Mine), 3);
+        assert!(board.validate().is_ok());
+    }
+
+    #[test]
+    fn placement_audit_counts_every_ship_cell() {
+        let counts = placement_audit((6, 6), Rules::default(), &CLASSIC_FLEET.to_vec(), (20, 7)).unwrap();
+        let total: usize = counts.iter().flatten().sum();
+        assert_eq!(total, 20 * CLASSIC_FLEET.iter().map(|&(_, size)| size).sum::<usize>());
+
+        //The same seeds give the same layouts
+        assert!(counts == placement_audit((6, 6), Rules::default(), &CLASSIC_FLEET.to_vec(), (20, 7)).unwrap());
+        assert!(placement_audit((2, 2), Rules::default(), &CLASSIC_FLEET.to_vec(), (1, 0)).is_err());
+    }
+
+    #[test]
+    fn template_boards_group_ship_cells_into_ships() {
+        let board = Board::from_template("
+            ##....
+            ......
+            .#..#.
+            .#..#.
+            .#..#.
+        ").unwrap();
+
+        assert_eq!((board.rows, board.columns), (5, 6));
+        let ships: Vec<(ShipKind, Position)> = board.ships.iter().map(|ship| (ship.kind, ship.cells[0])).collect();
+        assert_eq!(ships, vec![
+            (ShipKind::Destroyer, Position { row: 0, column: 0 }),
+            (ShipKind::Cruiser, Position { row: 2, column: 1 }),
+            (ShipKind::Submarine, Position { row: 2, column: 4 }), //The second ship of three cells
+        ]);
+        assert!(board.validate().is_ok());
+    }
+
+    #[test]
+    fn broken_templates_are_rejected() {
+        assert_eq!(Board::from_template("\n\n").err(), Some(TemplateError::Empty));
+        assert_eq!(Board::from_template("...\n..\n").err(), Some(TemplateError::RaggedRow { row: 1, length: 2 }));
+        assert_eq!(
+            Board::from_template("..\n.x\n").err(),
+            Some(TemplateError::UnknownCharacter { position: Position { row: 1, column: 1 }, character: 'x' })
+        );
+        assert_eq!(Board::from_template("##.\n.#.\n").err(), Some(TemplateError::Bent(Position { row: 0, column: 0 })));
+        assert_eq!(Board::from_template("#..\n...\n").err(), Some(TemplateError::NoShipOfSize(1)));
+    }
+
+    #[test]
+    fn cells_are_read_and_written_within_bounds() {
+        let mut board = Board::new(4, 6, BoardVisibility::Visible, Rules::default());
+        let corner = Position { row: 3, column: 5 };
+
+        assert!(board.set_cell(corner, CellState::Miss));
+        assert_eq!(board.cell(corner), Some(CellState::Miss));
+        assert_eq!(board.cell(Position { row: 4, column: 0 }), None);
+        assert!(!board.set_cell(Position { row: 0, column: 6 }, CellState::Miss));
+    }
+
+    #[test]
+    fn compact_boards_round_trip() {
+        let mut board = Board::from_template("
+            ##....
+            ......
+            .#..#.
+            .#..#.
+            .#..#.
+        ").unwrap();
+        board.grid[1][5] = CellState::Mine;
+        board.fire(Position { row: 0, column: 1 }).unwrap();
+        board.fire(Position { row: 3, column: 3 }).unwrap();
+        board.fire(Position { row: 1, column: 5 }).unwrap();
+
+        let line = board.to_compact();
+        assert_eq!(line, "5x6/#X9.!.#2.#2.#.o#2.#2.#./0@0,0h2;2@2,1v3;1@2,4v3");
+        let read = Board::from_compact(&line).unwrap();
+        assert!(read.grid == board.grid);
+        assert_eq!(read.to_compact(), line);
+        assert!(read.ships.iter().zip(&board.ships).all(|(a, b)| a.kind == b.kind && a.cells == b.cells));
+
+        assert_eq!(Board::from_compact("2x2/4.").err(), Some(CompactError::MissingPart));
+        assert_eq!(Board::from_compact("2x2/3./").err(), Some(CompactError::WrongCellCount { expected: 4, found: 3 }));
+        assert_eq!(Board::from_compact("2x2/2.2#/0@0,0h2").err(), Some(CompactError::BadShip("0@0,0h2".to_string())));
+        assert_eq!(Board::from_compact("0x3///").err(), Some(CompactError::NoCells));
+        assert_eq!(Board::from_compact("99999999999x99999999999/./").err(), Some(CompactError::TooLarge { rows: 99999999999, columns: 99999999999 }));
+        assert_eq!(Board::from_compact("2x2/999999999999.//").err(), Some(CompactError::WrongCellCount { expected: 4, found: 999999999999 })); //Not built first
+    }
+
+    #[test]
+    fn hunting_skips_the_border_of_a_sunk_ship_without_touching() {
+        let rules = Rules { adjacency: AdjacencyRule::NoTouch, ..Rules::default() };
+        let mut board = Board::new(6, 6, BoardVisibility::Visible, rules);
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+        board.place_ship_at(ShipKind::Submarine, Position { row: 5, column: 3 }, 3, Orientation::Horizontal).unwrap();
+
+        let mut strategy = HuntTargetStrategy::new(StdRng::seed_from_u64(1), AiState::default());
+        for position in [Position { row: 0, column: 0 }, Position { row: 0, column: 1 }] {
+            let result = board.fire(position).unwrap();
+            strategy.record(position, result);
+        }
+        let border = board.border(&board.ships[0]);
+
+        while let Some(position) = strategy.next_move(&board) {
+            assert!(!border.contains(&position), "fired at {:?} next to the sunk destroyer", position);
+            let result = board.fire(position).unwrap();
+            strategy.record(position, result);
+        }
+        assert!(board.game_over());
+        assert_eq!(strategy.state().sunk.len(), 2);
+    }
+
+    #[test]
+    fn firing_off_the_board_is_an_error() {
+        let mut board = Board::new(4, 6, BoardVisibility::Visible, Rules::default());
+        let before = board.grid.clone();
+
+        for position in [Position { row: 4, column: 0 }, Position { row: 0, column: 6 }, Position { row: usize::MAX, column: usize::MAX }] {
+            assert!(board.fire(position) == Err(OutOfBounds(position)));
+            assert!(board.fire_assisted(position) == Err(OutOfBounds(position)));
+            assert!(board.undo_fire(position) == Err(OutOfBounds(position)));
+            assert!(!already_fired(&board, position));
+        }
+        assert!(board.grid == before);
+        assert!(board.fire(Position { row: 3, column: 5 }) == Ok(FireResult::Miss));
+    }
+
+    #[test]
+    fn ship_cells_are_found_within_bounds() {
+        let mut board = Board::new(4, 6, BoardVisibility::Visible, Rules::default());
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 3, column: 4 }, 2, Orientation::Horizontal).unwrap();
+
+        assert!(board.is_ship_at(Position { row: 3, column: 5 }));
+        assert!(!board.is_ship_at(Position { row: 0, column: 0 }));
+        assert!(!board.is_ship_at(Position { row: 3, column: 6 }));
+        assert!(!board.is_ship_at(Position { row: 4, column: 5 }));
+
+        board.fire(Position { row: 3, column: 5 }).unwrap(); //A hit cell no longer counts
+        assert!(!board.is_ship_at(Position { row: 3, column: 5 }));
+        assert!(!board.game_over());
+        board.fire(Position { row: 3, column: 4 }).unwrap();
+        assert!(board.game_over());
+    }
+
+    #[test]
+    fn latest_shots_are_outlined_newest_first() {
+        let mut board = Board::new(1, 5, BoardVisibility::Visible, Rules::default());
+        board.palette = None;
+        board.recent = Some(VecDeque::new());
+        for column in 0..5 {
+            board.fire(Position { row: 0, column }).unwrap();
+        }
+        board.fire(Position { row: 0, column: 4 }).unwrap(); //Fired before, so nothing new
+        board.undo_fire(Position { row: 0, column: 3 }).unwrap();
+
+        assert!(board.recent == Some(VecDeque::from([Position { row: 0, column: 4 }, Position { row: 0, column: 2 }])));
+        assert_eq!(board.to_string().lines().nth(1), Some("0  o  o (o) \u{25A1} (o)"));
+    }
+
+    #[test]
+    fn fog_lifts_with_the_first_hit() {
+        let mut board = Board::new(1, 5, BoardVisibility::Hidden, Rules { fog: true, ..Rules::default() });
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 3 }, 2, Orientation::Horizontal).unwrap();
+        board.fire(Position { row: 0, column: 0 }).unwrap();
+
+        assert!(board.fogged());
+        assert_eq!(board.to_ascii().lines().nth(1), Some("0  .  .  .  .  . ")); //The miss is not shown yet
+        board.fire(Position { row: 0, column: 3 }).unwrap();
+        assert!(!board.fogged());
+        assert_eq!(board.to_ascii().lines().nth(1), Some("0  o  .  .  X  . "));
+
+        board.board_visibility = BoardVisibility::Visible; //Our own board is never fogged
+        board.undo_fire(Position { row: 0, column: 3 }).unwrap();
+        assert!(!board.fogged());
+    }
+
+    #[test]
+    fn ships_may_touch_under_default_rules() {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Cruiser, Position { row: 4, column: 2 }, 3, Orientation::Horizontal).unwrap();
+
+        assert!(board.can_place(&Position { row: 5, column: 2 }, 3, Orientation::Horizontal));
+        assert!(board.can_place(&Position { row: 1, column: 5 }, 3, Orientation::Vertical));
+    }
+
+    #[test]
+    fn mines_fill_only_water_and_detonate_once() {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Cruiser, Position { row: 0, column: 0 }, 3, Orientation::Horizontal).unwrap();
+        board.place_mines(200, &mut StdRng::seed_from_u64(1)); //More than fit
+
+        assert_eq!(board.count(CellState::Ship), 3);
+        assert_eq!(board.count(CellState::Empty), 0);
+
+        let mine = Position { row: 5, column: 5 };
+        assert!(board.fire(mine) == Ok(FireResult::Mine));
+        assert!(board.fire(mine) == Ok(FireResult::AlreadyFired));
+        board.undo_fire(mine).unwrap();
+        assert!(board.grid[5][5] == CellState::Mine);
+    }
+
+    #[test]
+    fn mines_of_a_placed_fleet_follow_the_seed() {
+        let rules = Rules { mines: 4, ..Rules::default() };
+        let mined = |seed| {
+            let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), seed, GameMode::Classic, Difficulty::Easy, rules, &CLASSIC_FLEET.to_vec(), PlayerPlacement::Manual).unwrap();
+            game.player_board.place_mines(rules.mines, &mut game.rng); //As main does once the fleet is placed
+            game.player_board.grid.clone()
+        };
+
+        assert!(mined(3) == mined(3));
+        assert!(mined(3) != mined(4));
+    }
+
+    #[test]
+    fn auto_target_follows_the_seed_and_skips_the_salvo() {
+        let game = || Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 8, GameMode::Salvo, Difficulty::Easy, Rules::default(), &CLASSIC_FLEET.to_vec(), PlayerPlacement::Random).unwrap();
+        let (mut first, mut second) = (game(), game());
+
+        let salvo: Vec<Position> = (0..DEFAULT_BOARD_SIZE).map(|column| Position { row: 4, column }).collect();
+        for _ in 0..5 {
+            let target = first.auto_target(&salvo);
+            assert_eq!(target, second.auto_target(&salvo));
+            assert!(target.is_some_and(|position| position.row != 4));
+        }
+    }
+
+    #[test]
+    fn sinking_reveals_the_border_and_undo_takes_it_back() {
+        let rules = Rules { adjacency: AdjacencyRule::NoTouch, reveal_sunk: true, ..Rules::default() };
+        let fleet = CLASSIC_FLEET.to_vec();
+        let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 3, GameMode::Classic, Difficulty::Easy, rules, &fleet, PlayerPlacement::Random).unwrap();
+        game.computer_board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, rules);
+        game.computer_board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+
+        //In the corner, so only 4 of the border cells are on the board, and one of them was already missed
+        for (row, column) in [(1, 0), (0, 0), (0, 1)] {
+            game.player_fire(Player::Human, Position { row, column }).unwrap();
+        }
+        assert_eq!(game.computer_board.count(CellState::Miss), 4);
+        assert_eq!(game.history.len(), 6);
+        assert_eq!(game.player_stats.shots, 3);
+
+        assert_eq!(game.undo_last_shot(), Some(Position { row: 0, column: 1 }));
+        assert_eq!(game.history.len(), 2);
+        assert_eq!(game.computer_board.grid[1][1], CellState::Empty);
+        assert_eq!(game.computer_board.grid[1][0], CellState::Miss); //Fired for real, so it stays
+        assert_eq!((game.player_stats.shots, game.player_stats.streak), (2, 1));
+        assert_eq!(game.player_stats.score, HIT_POINTS - MISS_PENALTY); //The sinking bonus is gone again
+    }
+
+    #[test]
+    fn undo_takes_back_a_held_turn_but_not_an_answered_one() {
+        let rules = Rules { mines: 1, ..Rules::default() };
+        let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 3, GameMode::Classic, Difficulty::Easy, rules, &CLASSIC_FLEET.to_vec(), PlayerPlacement::Random).unwrap();
+        let mine = (0..DEFAULT_BOARD_SIZE * DEFAULT_BOARD_SIZE)
+            .map(|cell| Position { row: cell / DEFAULT_BOARD_SIZE, column: cell % DEFAULT_BOARD_SIZE })
+            .find(|&position| game.computer_board.cell(position) == Some(CellState::Mine))
+            .unwrap();
+        game.hold_reply = true;
+
+        let outcome = game.step(InputAction::Fire(mine));
+        assert_eq!(outcome.shots[0].2, FireResult::Mine);
+        assert_eq!(outcome.turn, Player::Computer); //Held, nobody has answered
+        assert_eq!((game.turns, game.player_stats.turns, game.skip_turn), (1, 1, Some(Player::Human)));
+
+        let outcome = game.step(InputAction::Undo);
+        assert_eq!(outcome.undone, Some(mine));
+        assert_eq!(outcome.turn, Player::Human);
+        assert_eq!((game.turns, game.player_stats.turns, game.skip_turn), (0, 0, None));
+        assert_eq!((game.player_stats.shots, game.player_stats.score), (0, 0));
+        assert_eq!(game.computer_board.cell(mine), Some(CellState::Mine));
+
+        game.step(InputAction::Fire(mine));
+        game.computer_reply();
+        assert_eq!(game.step(InputAction::Undo).undone, None); //Too late once the computer fired
+        assert_eq!(game.player_stats.shots, 1);
+    }
+
+    #[test]
+    fn assisted_fire_measures_the_closest_intact_cell() {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 2, column: 2 }, 2, Orientation::Horizontal).unwrap();
+
+        assert!(board.fire_assisted(Position { row: 3, column: 4 }) == Ok((FireResult::Miss, Some(1)))); //Diagonally next to it
+        assert!(board.fire_assisted(Position { row: 4, column: 0 }) == Ok((FireResult::Miss, Some(2))));
+        assert!(board.fire_assisted(Position { row: 2, column: 3 }) == Ok((FireResult::Hit, Some(1)))); //Only the other end is left
+        assert!(board.fire_assisted(Position { row: 2, column: 2 }) == Ok((FireResult::Sunk(ShipKind::Destroyer), None)));
+    }
+
+    #[test]
+    fn scanned_cells_are_kept_like_shots() {
+        let mut game = classic_game(4);
+        let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 3 }, 2, Orientation::Horizontal).unwrap();
+        board.place_ship_at(ShipKind::Submarine, Position { row: 5, column: 0 }, 3, Orientation::Horizontal).unwrap();
+        game.computer_board = board;
+
+        assert_eq!(game.scan(Player::Human, Axis::Row, 0), 2); //Sinks the destroyer
+        assert!(game.computer_board.ships[0].is_sunk(&game.computer_board));
+        assert_eq!(game.history.len(), DEFAULT_BOARD_SIZE);
+        assert!(game.history.iter().filter(|&&(_, _, state)| state == CellState::Hit).count() == 2);
+        assert_eq!((game.player_stats.shots, game.player_stats.hits), (DEFAULT_BOARD_SIZE, 2));
+    }
+
+    #[test]
+    fn scan_fires_the_whole_line_but_hides_the_results() {
+        let mut board = Board::new(5, 6, BoardVisibility::Hidden, Rules::default());
+        board.place_ship_at(ShipKind::Cruiser, Position { row: 2, column: 1 }, 3, Orientation::Horizontal).unwrap();
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 5 }, 2, Orientation::Vertical).unwrap();
+        board.fire(Position { row: 2, column: 0 }).unwrap();
+
+        assert_eq!(board.scan(Axis::Row, 2), 3);
+        assert_eq!(board.scan(Axis::Column, 5), 2); //Sinks the destroyer, the cell it shares with row 2 was water
+        assert_eq!(board.scan(Axis::Row, 2), 0); //Nothing left to fire at
+        assert!(board.fire(Position { row: 2, column: 3 }) == Ok(FireResult::AlreadyFired));
+
+        assert_eq!(board.to_ascii().lines().nth(3), Some("2  o  ?  ?  ?  ?  ? "));
+        board.board_visibility = BoardVisibility::Visible;
+        assert_eq!(board.to_ascii().lines().nth(3), Some("2  o  X  X  X  o  o "));
+    }
+
+    #[test]
+    fn positions_parse_from_str_on_any_board() {
+        assert_eq!("3,4".parse::<Position>(), Ok(Position { row: 3, column: 4 }));
+        assert_eq!(" d 12 ".parse::<Position>(), Ok(Position { row: 12, column: 3 }));
+        assert_eq!("250, 300".parse::<Position>(), Ok(Position { row: 250, column: 300 })); //No board to be off
+        assert_eq!("".parse::<Position>(), Err(ParseError::Empty));
+        assert_eq!("3,x".parse::<Position>(), Err(ParseError::NotANumber("x".to_string())));
+    }
+
+    #[test]
+    fn several_cells_parse_from_one_line() {
+        let cells = parse_coordinates_multi("A1 b 7 3, 4 Z9 x", DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE);
+
+        assert_eq!(cells.len(), 5);
+        assert_eq!(cells[0], Ok(Position { row: 1, column: 0 }));
+        assert_eq!(cells[1], Ok(Position { row: 7, column: 1 })); //Letter and number may be split by a space
+        assert_eq!(cells[2], Ok(Position { row: 3, column: 4 }));
+        assert_eq!(cells[3], Err(ParseError::LetterOutOfRange('Z')));
+        assert!(cells[4].is_err());
+        assert!(parse_coordinates_multi("  ", DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE).is_empty());
+
+        let cells = parse_coordinates_multi("A1, B2, 3, 4, c 5", DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE);
+        assert_eq!(cells, vec![
+            Ok(Position { row: 1, column: 0 }),
+            Ok(Position { row: 2, column: 1 }),
+            Ok(Position { row: 3, column: 4 }),
+            Ok(Position { row: 5, column: 2 }),
+        ]);
+    }
+
+    #[test]
+    fn shortcuts_need_the_key_on_its_own() {
+        for command in ["surrender", "scan row 3", "save game.json", "reveal", "heatmap", "hint"] {
+            assert_eq!(expand_shortcut(command, &SHORTCUTS), command);
+        }
+        assert_eq!(expand_shortcut("s\n", &SHORTCUTS), "save");
+        assert_eq!(expand_shortcut(" h ", &SHORTCUTS), "history");
+        assert_eq!(expand_shortcut("x", &SHORTCUTS), "x");
+    }
+
+    #[test]
+    fn parse_errors_follow_the_language() {
+        let error = parse_coordinates("3", DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE).unwrap_err();
+
+        assert_eq!(error.to_string(), (Language::English.messages().parse_error)(&error));
+        assert_eq!((Language::German.messages().parse_error)(&error), "Erwartet werden eine Zeile und eine Spalte, eingegeben wurden 1 Feld(er).");
+
+        let error = check_fleet(&vec![(ShipKind::Carrier, 5)], 3, 3).unwrap_err();
+        assert_eq!(error.to_string(), "The Carrier does not fit on a board this small.");
+        assert_eq!((Language::German.messages().fleet_error)(&error), "Das Schiff Flugzeugträger passt nicht auf ein so kleines Brett.");
+        assert_eq!(parse_fleet("carrier,0").unwrap_err(), FleetSpecError::NoShipOfSize(0));
+    }
+
+    #[test]
+    fn drag_line_is_read_from_either_end() {
+        let line = |a: (usize, usize), b: (usize, usize)| Orientation::between(Position { row: a.0, column: a.1 }, Position { row: b.0, column: b.1 })
+            .map(|(start, orientation, length)| (start.row, start.column, orientation.cell(start, length - 1)));
+
+        assert!(matches!(line((2, 5), (2, 2)), Some((2, 2, Some(Position { row: 2, column: 5 })))));
+        assert!(matches!(line((6, 1), (3, 1)), Some((3, 1, Some(Position { row: 6, column: 1 })))));
+        assert!(matches!(line((4, 4), (2, 2)), Some((2, 2, Some(Position { row: 4, column: 4 })))));
+        assert!(matches!(line((4, 2), (2, 4)), Some((4, 2, Some(Position { row: 2, column: 4 })))));
+        assert!(line((0, 0), (1, 2)).is_none());
+    }
+
+    #[test]
+    fn screen_offsets_map_to_cells() {
+        let mut board = board();
+        assert!(board.cell_at(2, 1) == Some(Position { row: 0, column: 0 }));
+        assert!(board.cell_at(31, 10) == Some(Position { row: 9, column: 9 }));
+        assert!(board.cell_at(1, 1).is_none()); //The row label
+        assert!(board.cell_at(32, 10).is_none());
+
+        board.style = DisplayStyle::Grid;
+        assert!(board.cell_at(3, 2) == Some(Position { row: 0, column: 0 }));
+        assert!(board.cell_at(7, 4) == Some(Position { row: 1, column: 1 }));
+        assert!(board.cell_at(6, 2).is_none()); //A vertical line
+        assert!(board.cell_at(3, 3).is_none()); //A horizontal line
+    }
+
+    fn loaded_board() -> Board {
+        let mut board = board();
+        board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+        board.place_ship_at(ShipKind::Cruiser, Position { row: 2, column: 2 }, 3, Orientation::Vertical).unwrap();
+        board
+    }
+
+    #[test]
+    fn consistent_board_passes_validation() {
+        assert!(loaded_board().validate().is_ok());
+    }
+
+    #[test]
+    fn short_grid_is_rejected() {
+        let mut board = loaded_board();
+        board.grid.pop();
+        assert!(matches!(board.validate(), Err(LoadError::GridSize)));
+
+        let mut board = loaded_board();
+        board.grid[4].pop();
+        assert!(matches!(board.validate(), Err(LoadError::GridSize)));
+    }
+
+    #[test]
+    fn ship_off_the_grid_is_rejected() {
+        let mut board = loaded_board();
+        board.ships[1].cells[2] = Position { row: DEFAULT_BOARD_SIZE, column: 2 };
+        assert!(matches!(board.validate(), Err(LoadError::ShipOffBoard(ShipKind::Cruiser))));
+    }
+
+    #[test]
+    fn overlapping_saved_ships_are_rejected() {
+        let mut board = loaded_board();
+        board.ships[1].cells[0] = Position { row: 0, column: 1 }; //Also one of the destroyer's cells
+        assert!(matches!(board.validate(), Err(LoadError::ShipOverlap(ShipKind::Cruiser))));
+    }
+
+    #[test]
+    fn ship_cells_must_match_the_grid() {
+        let mut board = loaded_board();
+        board.grid[3][2] = CellState::Miss;
+        assert!(matches!(board.validate(), Err(LoadError::ShipMismatch(ShipKind::Cruiser))));
+
+        let mut board = loaded_board();
+        board.ships[0].cells.pop();
+        assert!(matches!(board.validate(), Err(LoadError::ShipMismatch(ShipKind::Destroyer))));
+    }
+
+    #[test]
+    fn unowned_ship_cell_is_rejected() {
+        let mut board = loaded_board();
+        board.grid[7][7] = CellState::Ship;
+        assert!(matches!(board.validate(), Err(LoadError::StrayShipCell(Position { row: 7, column: 7 }))));
+    }
+
+    #[test]
+    fn sinking_a_ship_in_a_row_scores_a_bonus() {
+        let mut stats = Stats::default();
+        stats.record(FireResult::Hit);
+        stats.record(FireResult::Hit);
+        stats.record(FireResult::Sunk(ShipKind::Submarine));
+        assert_eq!(stats.score, 3 * HIT_POINTS + 2 * STREAK_BONUS);
+
+        stats.record(FireResult::Miss);
+        stats.record(FireResult::Hit);
+        stats.record(FireResult::Mine);
+        stats.record(FireResult::Sunk(ShipKind::Destroyer)); //The miss in between costs the bonus
+        assert_eq!(stats.score, 5 * HIT_POINTS + 2 * STREAK_BONUS - 2 * MISS_PENALTY);
+        stats.record(FireResult::AlreadyFired);
+        assert_eq!(stats.score, 5 * HIT_POINTS + 2 * STREAK_BONUS - 2 * MISS_PENALTY);
+    }
+
+    #[test]
+    fn stats_are_written_as_json() {
+        let mut game = classic_game(11);
+        game.player_stats.record(FireResult::Hit);
+        game.player_stats.record(FireResult::Miss);
+        game.player_stats.think_times = vec![Duration::from_millis(1500), Duration::from_millis(500)];
+
+        let file = temp_file("stats");
+        let path = &file.0;
+        save_stats(&game, Some(Player::Human), path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+
+        assert_eq!(json["seed"], 11);
+        assert_eq!(json["winner"], "Human");
+        assert_eq!(json["player"]["shots"], 2);
+        assert_eq!(json["player"]["accuracy"], 50.0);
+        assert_eq!(json["computer"]["hits"], 0);
+        assert_eq!(json["player"]["think_time"]["average_ms"], 1000);
+        assert_eq!(json["player"]["think_time"]["fastest_ms"], 500);
+        assert_eq!(json["player"]["think_time"]["slowest_ms"], 1500);
+        assert!(json["computer"].get("think_time").is_none()); //The computer is never timed
+    }
+
+    #[test]
+    fn profile_adds_up_over_runs() {
+        let path = std::env::temp_dir().join(format!("battleship_profile_{}", std::process::id())).join("record.json");
+        let path = path.to_str().unwrap();
+        let mut profile = read_profile(path).unwrap();
+        assert_eq!(profile, Profile::default()); //Nothing saved yet
+
+        let stats = Stats { shots: 4, hits: 3, misses: 1, ..Stats::default() };
+        profile.add(Some(Player::Human), &stats);
+        profile.add(None, &stats);
+        save_profile(&profile, path).unwrap(); //Also creates the directory
+        let loaded = read_profile(path).unwrap();
+        fs::remove_dir_all(std::path::Path::new(path).parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, Profile { played: 2, won: 1, lost: 0, shots: 8, hits: 6 });
+        assert_eq!(loaded.accuracy(), 75.0);
+    }
+
+    #[test]
+    fn hotseat_save_on_player_two_turn_keeps_the_sides() {
+        let fleet = CLASSIC_FLEET.to_vec();
+        let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 6, GameMode::Hotseat, Difficulty::Easy, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+        game.player_scans = 2;
+        game.player_stats.shots = 3;
+        game.turn = Player::Computer;
+        game.swap_sides(); //Player two takes the seat, fires and saves
+        game.computer_board.fire(Position { row: 0, column: 0 }).unwrap();
+        let (one, two) = (game.computer_board.grid.clone(), game.player_board.grid.clone());
+
+        let file = temp_file("hotseat");
+        let path = &file.0;
+        save_game(&game, path).unwrap();
+        let loaded = load_game(path).unwrap();
+
+        assert!(loaded.player_board.grid == one && loaded.computer_board.grid == two); //Player one's board is theirs again, with the shot on it
+        assert!(matches!(loaded.computer_board.board_visibility, BoardVisibility::Hidden));
+        assert_eq!((loaded.player_scans, loaded.computer_scans), (2, 0));
+        assert!(loaded.turn == Player::Computer && !loaded.swapped);
+        assert_eq!((loaded.player_stats.shots, loaded.computer_stats.shots), (3, 0));
+    }
+
+    #[test]
+    fn saved_game_keeps_the_stats_and_turn_limit() {
+        let mut game = classic_game(4);
+        game.max_turns = Some(20);
+        let target = game.computer_board.ships[0].cells[0];
+        game.player_fire(Player::Human, target).unwrap();
+        game.computer_turn();
+
+        let file = temp_file("saved_stats");
+        let path = &file.0;
+        save_game(&game, path).unwrap();
+        let loaded = load_game(path).unwrap();
+
+        assert_eq!((loaded.player_stats.shots, loaded.player_stats.hits, loaded.player_stats.score), (1, 1, game.player_stats.score));
+        assert_eq!((loaded.computer_stats.shots, loaded.computer_stats.turns), (1, 1));
+        assert_eq!(loaded.max_turns, Some(20));
+    }
+
+    #[test]
+    fn shot_off_the_board_in_history_is_rejected() {
+        let mut game = classic_game(3);
+        game.history.push((Player::Human, Position { row: 0, column: DEFAULT_BOARD_SIZE }, CellState::Miss));
+
+        let file = temp_file("history");
+        let path = &file.0;
+        save_game(&game, path).unwrap();
+        let loaded = read_save(path);
+
+        assert!(matches!(loaded, Err(LoadError::ShotOffBoard(Position { row: 0, column: DEFAULT_BOARD_SIZE }))));
+    }
+
+    #[test]
+    fn date_seed_reads_as_the_date() {
+        assert_eq!(date_seed(0), 19700101);
+        assert_eq!(date_seed(59), 19700301);
+        assert_eq!(date_seed(11_016), 20000229); //A leap day in a year divisible by 400
+        assert_eq!(date_seed(20_740), 20261014);
+    }
+
+    #[test]
+    fn team_games_need_every_enemy_board_sunk() {
+        let fleet = CLASSIC_FLEET.to_vec();
+        let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 5, GameMode::Teams, Difficulty::Easy, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+        assert_eq!((game.player_boards.len(), game.computer_boards.len(), game.team_opponents.len()), (1, 1, 1));
+
+        let second = game.computer_boards[0].grid.clone();
+        game.aim_at(Player::Computer, 1);
+        assert!(game.computer_board.grid == second);
+        assert!(game.boards(Player::Computer)[1].grid == second); //Still listed as the second board
+
+        let cells: Vec<Position> = game.computer_board.ships.iter().flat_map(|ship| ship.cells.clone()).collect();
+        for position in cells {
+            game.computer_board.fire(position).unwrap();
+        }
+        assert!(game.computer_board.game_over() && !game.all_sunk(Player::Computer));
+        assert_eq!(game.afloat(Player::Computer), (4, 8)); //Counted over both boards
+        assert_eq!(game.afloat(Player::Human), (8, 8));
+
+        game.aim_at(Player::Computer, 0);
+        assert!(game.computer_boards[0].game_over());
+
+        //The computer follows up on a hit before searching the other board
+        assert_eq!(game.team_target(), 0);
+        let hit = game.player_boards[0].ships[0].cells[0];
+        game.player_boards[0].fire(hit).unwrap();
+        assert_eq!(game.team_target(), 1);
+    }
+
+    #[test]
+    fn plain_board_tells_hits_from_misses_without_colour() {
+        let mut board = loaded_board();
+        board.palette = None;
+        board.fire(Position { row: 0, column: 0 }).unwrap();
+        board.fire(Position { row: 0, column: 5 }).unwrap();
+
+        let first_row = board.to_string().lines().nth(1).unwrap().to_string();
+        assert!(!first_row.contains('\u{1b}'));
+        assert!(first_row.starts_with("0  X  \u{25A0}  \u{25A1}  \u{25A1}  \u{25A1}  o "));
+
+        board.fire(Position { row: 0, column: 1 }).unwrap(); //Sinks the destroyer
+        assert!(board.to_string().lines().nth(1).unwrap().starts_with("0  \u{25A3}  \u{25A3}  \u{25A1}"));
+    }
+
+    #[test]
+    fn themes_swap_the_glyphs() {
+        let mut board = loaded_board(); //Destroyer on A0 and B0, cruiser from C2 down
+        board.theme = RenderTheme::ASCII;
+        board.fire(Position { row: 0, column: 0 }).unwrap();
+        board.fire(Position { row: 0, column: 5 }).unwrap();
+
+        board.palette = None;
+        assert!(board.to_string().lines().nth(1).unwrap().starts_with("0  X  #  .  .  .  o "));
+
+        board.theme = RenderTheme::EMOJI;
+        let first_row = board.to_string().lines().nth(1).unwrap().to_string();
+        assert!(first_row.starts_with("0  \u{1F4A5} \u{1F6A2} \u{1F30A}"));
+    }
+
+    #[test]
+    fn fire_by_notation_reports_the_shot() {
+        let mut board = loaded_board(); //Destroyer on A0 and B0, cruiser from C2 down
+
+        assert_eq!(board.fire_by_notation("B0"), Ok(FireResult::Hit));
+        assert_eq!(board.fire_by_notation("2, 2"), Ok(FireResult::Hit));
+        assert_eq!(board.fire_by_notation("j9"), Ok(FireResult::Miss));
+        assert_eq!(board.fire_by_notation("J9"), Ok(FireResult::AlreadyFired)); //Fired twice, nothing changed
+        assert!(board.grid[0][1] == CellState::Hit);
+        assert!(board.grid[9][9] == CellState::Miss);
+    }
+
+    #[test]
+    fn fire_by_notation_rejects_bad_input() {
+        let mut board = loaded_board();
+
+        assert_eq!(board.fire_by_notation(""), Err(ParseError::Empty));
+        assert_eq!(board.fire_by_notation("1, 2, 3"), Err(ParseError::WrongFieldCount(3)));
+        assert_eq!(board.fire_by_notation("x, 2"), Err(ParseError::NotANumber("x".to_string())));
+        assert_eq!(board.fire_by_notation("10, 2"), Err(ParseError::OutOfRange { axis: Axis::Row, value: 10 }));
+        assert_eq!(board.fire_by_notation("K3"), Err(ParseError::LetterOutOfRange('K')));
+        assert!(board.grid.iter().flatten().all(|&cell| matches!(cell, CellState::Empty | CellState::Ship)));
+    }
+}
@@ -1,265 +1,1899 @@
+use std::collections::VecDeque;
 use std::fmt::{Display, Formatter};
-use std::io::{self, stdout, Write};
-use crossterm::{execute, terminal::{Clear, ClearType}, cursor::MoveTo, style::Stylize};
-use rand::Rng;
+use std::io::{self, stdout, BufRead, BufReader, IsTerminal, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crossterm::{execute, terminal::{self, Clear, ClearType}, cursor::{MoveTo, RestorePosition, SavePosition, Show}, style::{Color, Print, Stylize}};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap::error::ErrorKind;
+use battleship::*;
 
+const DEFAULT_SAVE_PATH: &str = "battleship_save.json";
+const RECENT_SHOTS: usize = 6; //How many shots of the history are shown under the boards
+const DEFAULT_REPLAY_DELAY_MS: u64 = 800; //Pause between moves when replaying
+const FLASH_MS: u64 = 400; //How long a rejected drag stays red
+const BANNER_FLASHES: usize = 3; //Blinks of the banner when a new shot lands
+const BANNER_FLASH_MS: u64 = 120;
+const WARM_DISTANCE: usize = 1; //Right next to the closest intact ship cell for a warm miss, diagonals included
+const COLD_DISTANCE: usize = 4; //...and from here on it is cold
 
-const BOARD_SIZE: usize = 10; //10 * 10 game board
+fn render(game: &Game) -> String { //The whole screen for the player's turn
+    let text = game.language.messages();
+    let recent: Vec<String> = game.history.iter()
+        .skip(game.history.len().saturating_sub(RECENT_SHOTS))
+        .map(|shot| game.format_shot(shot))
+        .collect();
 
-#[derive(Copy, Clone, PartialEq)]
-enum CellState {
-    Empty,
-    Ship,
-    Hit,
-    Miss,
+    let side = |title: &str, side: Player| -> String { //Numbered when a team has more than one board
+        let boards = game.boards(side);
+        boards.iter().enumerate().map(|(index, board)| {
+            let title = if boards.len() > 1 { (text.team_board)(title, index + 1) } else { title.to_string() };
+            format!("{}: \n{}\n", title, beside(&game.renderer.render(board), &render_status(board, text)))
+        }).collect()
+    };
+
+    let lifetime = match (&game.profile, game.turns) { //Only on the first screen of a game
+        (Some(profile), 0) => format!("{}\n", (text.lifetime)(profile)),
+        _ => String::new(),
+    };
+
+    format!(
+        "{}\n{}: {}\n{}{}{}{}: {}\n{}\n",
+        turn_header(game),
+        text.seed, game.seed,
+        lifetime,
+        side(text.your_ships, Player::Human),
+        side(text.their_ships, Player::Computer),
+        text.last_shots, recent.join(", "),
+        (text.running_score)(game.player_stats.score, game.computer_stats.score),
+    )
+}
+
+fn turn_header(game: &Game) -> String { //Whose turn it is, in big letters over the boards
+    let text = game.language.messages();
+    let label = match (game.mode, game.turn) {
+        (GameMode::Hotseat, side) => Some(game.player_label(side)), //Both are "you" on a shared screen
+        (_, Player::Human) => None,
+        (_, Player::Computer) => Some(game.player_label(Player::Computer)),
+    };
+    shout(&(text.turn_header)(label.as_deref()), game.player_board.palette)
+}
+
+fn visible_width(line: &str) -> usize { //Characters on screen, without colour escape codes
+    let mut width = 0;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic()); //The sequence ends in a letter
+        } else {
+            width += 1;
+        }
+    }
+
+    width
+}
+
+fn redraw(game: &Game, raw: bool) { //The whole screen from scratch, also whenever the terminal is resized
+    let text = game.language.messages();
+    let mut screen = render(game);
+
+    //Warn rather than draw boards that wrap around, the prompt needs a couple of lines too
+    let needed = (screen.lines().map(visible_width).max().unwrap_or(0), screen.lines().count() + 2);
+    let size = terminal::size().ok().filter(|_| stdout().is_terminal()); //A pipe has no size, only a made-up default
+    if let Some((columns, rows)) = size.filter(|&(columns, rows)| columns > 0 && rows > 0) { //Zero when the size is unknown
+        if (columns as usize) < needed.0 || (rows as usize) < needed.1 {
+            let warning = (text.too_small)(columns, rows, needed.0, needed.1);
+            screen.push_str(&tint(&warning, game.player_board.palette, |palette| palette.warning));
+            screen.push('\n');
+        }
+    }
+    if raw { //Raw mode does not return the carriage on a newline
+        screen = screen.replace('\n', "\r\n");
+    }
+
+    let mut stdout = stdout();
+    execute!(stdout, Clear(ClearType::All), Clear(ClearType::Purge), MoveTo(0, 0)).unwrap();
+    print!("{}", screen);
+    show_banner(game, false);
+    stdout.flush().unwrap();
+}
+
+fn render_status(board: &Board, text: &Messages) -> Vec<String> { //Legend of which ships in a fleet are still afloat
+    let mut lines = vec![text.fleet.to_string()];
+    let own = matches!(board.board_visibility, BoardVisibility::Visible); //Damage to the enemy fleet stays secret until it sinks
+    let width = board.ships.iter().map(|ship| (text.ship)(ship.kind).chars().count()).max().unwrap_or(0);
+
+    for ship in &board.ships {
+        let name = if own { format!("{:<width$}", (text.ship)(ship.kind)) } else { (text.ship)(ship.kind).to_string() };
+        let name = if ship.is_sunk(board) && board.palette.is_none() {
+            format!("{} ({})", name, text.sunk)
+        } else if ship.is_sunk(board) {
+            name.crossed_out().dim().to_string()
+        } else {
+            name
+        };
+
+        if own {
+            lines.push(format!("{} {}", name, health_bar(ship.damage(board), board)));
+        } else {
+            lines.push(name);
+        }
+    }
+
+    if !board.ships.is_empty() { //A network opponent's fleet is not known here
+        lines.push(String::new());
+        lines.push((text.ships_remaining)(board.remaining_ship_cells()));
+    }
+
+    lines
+}
+
+fn health_bar((hits, size): (usize, usize), board: &Board) -> String { //One block per cell, the hit ones empty
+    //Emoji would make the bars twice as long, blocks are fine for them
+    let theme = if board.theme.wide { RenderTheme::UNICODE } else { board.theme };
+    let bar = format!("[{}{}]", theme.ship.repeat(size - hits), theme.water.repeat(hits));
+
+    match board.palette {
+        None => bar,
+        Some(palette) if hits == 0 => bar.with(palette.ship).to_string(),
+        Some(palette) if hits * 2 < size => bar.with(palette.warning).to_string(),
+        Some(palette) => bar.with(palette.hit).to_string(), //Half or more gone
+    }
 }
 
-enum BoardVisibility {
-    Visible,
-    Hidden,
+fn tint(message: &str, palette: Option<Palette>, color: fn(&Palette) -> Color) -> String { //Plain text when colours are off
+    match palette {
+        Some(palette) => message.with(color(&palette)).to_string(),
+        None => message.to_string(),
+    }
 }
 
-#[derive(Copy, Clone)]
-struct Position {
-    row: usize,
-    column: usize,
+fn shout(message: &str, palette: Option<Palette>) -> String { //A sinking, bold in the colour of a hit
+    match palette {
+        Some(palette) => message.with(palette.hit).bold().to_string(),
+        None => message.to_string(),
+    }
 }
 
-struct Board {
-    grid: [[CellState; BOARD_SIZE]; BOARD_SIZE],
-    ships: Vec<Position>, //Stores the Position of the ships
-    board_visibility: BoardVisibility,
+fn show_banner(game: &Game, animate: bool) { //The latest shot, pinned to the top right corner until the next one
+    let Some(&(player, position, state)) = game.history.last().filter(|_| game.banner) else {
+        return;
+    };
+
+    let text = game.language.messages();
+    let (word, color): (&str, fn(&Palette) -> Color) = match state {
+        CellState::Hit => (text.banner_hit, |palette| palette.hit),
+        CellState::Detonated => (text.banner_mine, |palette| palette.warning),
+        _ => (text.banner_miss, |palette| palette.miss),
+    };
+    let banner = format!(" {} ", (text.banner)(&game.player_label(player), word, &game.notation.format(position)));
+    let width = terminal::size().map(|(columns, _)| columns as usize).unwrap_or(80);
+    let x = width.saturating_sub(banner.chars().count()) as u16;
+
+    let frame = |highlight: bool| match game.player_board.palette {
+        Some(palette) if highlight => banner.as_str().with(color(&palette)).reverse().bold().to_string(),
+        Some(palette) => banner.as_str().with(color(&palette)).to_string(),
+        None => banner.clone(),
+    };
+
+    let mut stdout = stdout();
+    let frames = if animate { BANNER_FLASHES * 2 } else { 0 };
+    for i in (0..=frames).rev() { //Ends on the highlighted frame
+        execute!(stdout, SavePosition, MoveTo(x, 0), Print(frame(i % 2 == 0)), RestorePosition).unwrap();
+        if i > 0 {
+            thread::sleep(Duration::from_millis(BANNER_FLASH_MS));
+        }
+    }
 }
 
-#[derive(Copy, Clone)]
-enum Orientation { //Denotes the orientation of the ship
-    Horizontal,
-    Vertical,
+fn beside(board: &str, panel: &[String]) -> String { //Put a panel to the right of a rendered board
+    let mut panel = panel.iter();
+
+    board.lines().map(|line| match panel.next() {
+        Some(entry) => format!("{}   {}\n", line, entry),
+        None => format!("{}\n", line),
+    }).collect()
 }
 
-impl Board {
-    fn new(board_visibility: BoardVisibility) -> Self {
-        Board {
-            grid: [[CellState::Empty; BOARD_SIZE]; BOARD_SIZE],
-            ships: Vec::new(),
-            board_visibility,
+fn print_final_boards(game: &Game) { //Every board in plain text with each ship, hit and miss, ours on the left
+    let text = game.language.messages();
+    let uncovered = |board: &Board| { //A copy, the game is still saved with the enemy fleet hidden
+        let mut board = board.clone();
+        board.board_visibility = BoardVisibility::Visible;
+        AsciiRenderer.render(&board)
+    };
+
+    println!("{}", text.final_boards);
+    for (ours, theirs) in game.boards(Player::Human).into_iter().zip(game.boards(Player::Computer)) {
+        let ours = uncovered(ours);
+        let width = ours.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let theirs: Vec<String> = uncovered(theirs).lines().map(String::from).collect();
+        println!("{:<width$}   {}", game.player_label(Player::Human), game.player_label(Player::Computer), width = width);
+        print!("{}", beside(&ours, &theirs));
+    }
+}
+
+fn cursor_input(game: &mut Game) -> InputAction { //Aim with the arrow keys, Esc quits
+    let (rows, columns) = (game.computer_board.rows, game.computer_board.columns);
+    let mut cursor = game.computer_board.cursor.unwrap_or(Position { row: rows / 2, column: columns / 2 });
+
+    let Some(_raw) = RawMode::enable() else { //Not a real terminal, fall back to typing coordinates
+        return user_input(game, None); //No timer without a terminal to poll
+    };
+
+    let target = loop {
+        game.computer_board.cursor = Some(cursor);
+
+        redraw(game, true);
+        print!("{}\r\n", game.language.messages().cursor_help);
+        stdout().flush().unwrap();
+
+        let Ok(event) = event::read() else {
+            break None;
+        };
+
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Up => cursor.row = cursor.row.saturating_sub(1),
+                KeyCode::Down => cursor.row = (cursor.row + 1).min(rows - 1),
+                KeyCode::Left => cursor.column = cursor.column.saturating_sub(1),
+                KeyCode::Right => cursor.column = (cursor.column + 1).min(columns - 1),
+                KeyCode::Enter if !already_fired(&game.computer_board, cursor) => break Some(cursor),
+                KeyCode::Esc => break None,
+                _ => ()
+            },
+            Event::Resize(..) => (), //Redrawn at the top of the loop anyway
+            _ => ()
+        }
+    };
+
+    match target {
+        Some(position) => InputAction::Fire(position),
+        None => {
+            game.computer_board.cursor = None;
+            InputAction::Quit
         }
     }
+}
 
-    fn place_ship(&mut self, size: usize) { //size is the size of the ship
-        let mut rng = rand::thread_rng();
+enum Line { //What came of waiting for a line of input
+    Typed(String),
+    TimeUp, //The deadline passed first
+    Closed, //Nothing more will ever come, e.g. piped input ran out
+}
 
-        loop {
-            let position = Position {
-                row: rng.gen_range(0..BOARD_SIZE),
-                column: rng.gen_range(0..BOARD_SIZE),
-            };
+fn read_stdin() -> Option<String> { //None once stdin is closed or cannot be read
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input),
+    }
+}
+
+fn read_line_raw(prompt: &str, deadline: Option<Instant>, redraw: &dyn Fn()) -> Line {
+    let Some(raw) = RawMode::enable() else { //Not a real terminal, so no clock and no resizing either
+        print!("{}", prompt);
+        stdout().flush().unwrap();
+        return read_stdin().map_or(Line::Closed, Line::Typed);
+    };
+
+    let mut input = String::new();
+    let line = loop {
+        let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+        if left.is_some_and(|left| left.is_zero()) {
+            break Line::TimeUp;
+        }
+
+        let mut stdout = stdout();
+        execute!(stdout, Clear(ClearType::CurrentLine)).unwrap();
+        match left {
+            Some(left) => print!("\r[{:>2}s] {}{}", left.as_millis().div_ceil(1000), prompt, input),
+            None => print!("\r{}{}", prompt, input),
+        }
+        stdout.flush().unwrap();
+
+        //Wake up at least every quarter second so the countdown keeps ticking
+        let tick = Duration::from_millis(250);
+        if !event::poll(left.map_or(tick, |left| left.min(tick))).unwrap_or(false) {
+            continue;
+        }
+
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    restore_terminal(); //Exiting skips the guard
+                    std::process::exit(130); //Raw mode swallows the usual interrupt
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) && input.is_empty() => break Line::Closed,
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => break Line::Typed(input),
+                _ => ()
+            },
+            Ok(Event::Resize(..)) => redraw(), //The old layout may be smeared across the new size
+            Ok(_) => (),
+            Err(_) => break Line::Closed,
+        }
+    };
+
+    drop(raw);
+    println!();
+    line
+}
+
+fn user_input(game: &mut Game, limit: Option<Duration>) -> InputAction {
+    let (rows, columns) = (game.computer_board.rows, game.computer_board.columns);
+    let deadline = limit.map(|limit| Instant::now() + limit); //Commands and typos still count against the clock
+    let text = game.language.messages();
+    let prompt = text.fire_prompt;
+
+    if io::stdin().is_terminal() {
+        let keys: Vec<String> = SHORTCUTS.iter().map(|(key, command)| format!("{} {}", key, command)).collect();
+        println!("{}", (text.shortcut_keys)(&keys.join(", ")));
+    }
+
+    loop {
+        if let Some(position) = game.queued.pop_front() { //Typed ahead with burst
+            let cell = game.notation.format(position);
+            if already_fired(&game.computer_board, position) { //Uncovered since, e.g. around a sunk ship
+                println!("{}", (text.queued_skipped)(&cell));
+                continue;
+            }
+            println!("{}", (text.queued_shot)(&cell, game.queued.len()));
+            return InputAction::Queued(position);
+        }
+
+        let input = match read_line_raw(prompt, deadline, &|| redraw(game, true)) {
+            Line::Typed(input) => expand_shortcut(&input, &SHORTCUTS).to_string(),
+            Line::TimeUp => return InputAction::TimeUp,
+            Line::Closed => {
+                println!("{}", text.input_closed);
+                return InputAction::Quit;
+            }
+        };
+
+        let mut words = input.split_whitespace();
+        let command = words.next();
+
+        if matches!(command, Some("quit" | "q")) {
+            return InputAction::Quit;
+        }
+
+        if command == Some("surrender") {
+            return InputAction::Surrender;
+        }
 
-            let direction = match rng.gen_range(0..2) {
-                0 => Orientation::Horizontal,
-                _ => Orientation::Vertical,
+        if command == Some("burst") && matches!(game.mode, GameMode::Hotseat | GameMode::Network) {
+            println!("{}", text.burst_needs_computer); //Hotseat players would share the queue
+            continue;
+        }
+
+        if command == Some("burst") { //e.g. burst A1 B2 C3, one shot a turn until they run out
+            let cells = input.trim_start().strip_prefix("burst").unwrap_or_default();
+            let mut queued: Vec<Position> = Vec::new();
+            for cell in parse_coordinates_multi(cells, rows, columns) {
+                match cell {
+                    Ok(position) if queued.contains(&position) => println!("{}", (text.burst_duplicate)(&game.notation.format(position))),
+                    Ok(position) if already_fired(&game.computer_board, position) => {
+                        println!("{}", (text.already_fired_at)(&game.notation.format(position)));
+                    }
+                    Ok(position) => queued.push(position),
+                    Err(e) => println!("{}", (text.parse_error)(&e)),
+                }
+            }
+            if queued.is_empty() {
+                println!("{}", text.burst_usage);
+            } else {
+                game.queued.extend(queued);
+                game.burst = Some((game.player_stats.hits, game.player_stats.misses));
+            }
+            continue;
+        }
+
+        if matches!(command, Some("auto" | "hint")) {
+            if game.auto_fire {
+                return InputAction::Auto;
+            }
+            println!("{}", text.auto_needs_flag);
+            continue;
+        }
+
+        if command == Some("fast") { //Stop waiting for Enter between turns, or start again
+            game.pause = !game.pause;
+            println!("{}", if game.pause { text.fast_off } else { text.fast_on });
+            continue;
+        }
+
+        if command == Some("undo") { //Only possible before the opponent answers, e.g. in a salvo or after a chained hit
+            if !game.takeback.is_empty() {
+                return InputAction::Undo;
+            }
+            match game.history.last() {
+                Some((Player::Computer, _, _)) => println!("{}", text.too_late_to_undo),
+                _ => println!("{}", text.nothing_to_undo),
+            }
+            continue;
+        }
+
+        if command == Some("reveal") { //Debugging aid, shows the enemy fleet once
+            if !game.debug {
+                println!("{}", text.reveal_needs_debug);
+                continue;
+            }
+
+            let mut revealed = game.computer_board.clone();
+            revealed.board_visibility = BoardVisibility::Visible;
+            println!("{}", revealed);
+            continue;
+        }
+
+        if command == Some("heatmap") { //Debugging aid, how the remaining fleet could still be laid out
+            if !game.debug {
+                println!("{}", text.heatmap_needs_debug);
+                continue;
+            }
+
+            let mut heat = game.computer_board.clone();
+            heat.heat = Some(game.computer_board.heatmap());
+            redraw(game, false);
+            println!("{}", heat);
+            println!("{}", text.heatmap_legend);
+            read_line_raw(text.press_enter, None, &|| {
+                redraw(game, true);
+                print!("{}", heat.to_string().replace('\n', "\r\n"));
+            });
+            redraw(game, false); //Back to the normal boards
+            continue;
+        }
+
+        if command == Some("ascii") { //Both boards as plain text, e.g. for a bug report
+            println!("{}\n{}", AsciiRenderer.render(&game.player_board), AsciiRenderer.render(&game.computer_board));
+            continue;
+        }
+
+        if command == Some("radar") { //Just the enemy grid, without our own fleet
+            let board = &game.computer_board;
+            println!("{}", game.renderer.render(board));
+            println!("{}", (text.radar_tally)(board.count(CellState::Hit), board.count(CellState::Miss)));
+            continue;
+        }
+
+        if command == Some("list-hits") { //The enemy cells we hit as plain text, for terminals that garble the grid
+            let board = &game.computer_board;
+            let hits: Vec<String> = (0..board.rows)
+                .flat_map(|row| (0..board.columns).map(move |column| Position { row, column }))
+                .filter(|&position| board.cell(position) == Some(CellState::Hit))
+                .map(|position| game.notation.format(position))
+                .collect();
+            if hits.is_empty() {
+                println!("{}", text.no_hits_yet);
+            } else {
+                println!("{}", (text.hits_list)(hits.len(), &hits.join(", ")));
+            }
+            continue;
+        }
+
+        if command == Some("scan") { //e.g. scan row 3 or scan column B
+            if game.player_scans == 0 {
+                println!("{}", text.no_scans_left);
+                continue;
+            }
+
+            //Columns may also be given by their letter
+            let index = |token: &str| token.parse().ok().or_else(|| match token.as_bytes() {
+                [letter] if letter.is_ascii_alphabetic() => Some((letter.to_ascii_uppercase() - b'A') as usize),
+                _ => None,
+            });
+            let line = match (words.next(), words.next().and_then(index)) {
+                (Some("row"), Some(row)) if row < rows => Some((Axis::Row, row)),
+                (Some("column"), Some(column)) if column < columns => Some((Axis::Column, column)),
+                _ => None,
             };
+            match line {
+                Some((axis, index)) if game.computer_board.line(axis, index).into_iter().all(|cell| already_fired(&game.computer_board, cell)) => {
+                    println!("{}", text.nothing_to_scan);
+                }
+                Some((axis, index)) => return InputAction::Scan(axis, index),
+                None => println!("{}", (text.scan_usage)(rows - 1, columns - 1)),
+            }
+            continue;
+        }
 
-            if self.can_place(&position, size, direction) {
-                for i in 0..size {
-                    let (ship_row, ship_col) = match direction {
-                        Orientation::Horizontal => (position.row, position.column + i),
-                        Orientation::Vertical => (position.row + i, position.column)
-                    };
-
-                    self.grid[ship_row][ship_col] = CellState::Ship;
-                    self.ships.push(Position {
-                        row: ship_row,
-                        column: ship_col,
-                    });
+        if command == Some("peek") { //Uncovers a ship cell and leaves the shot still to fire
+            match game.peek(Player::Human) {
+                Some(position) => {
+                    redraw(game, false);
+                    println!("{}", (text.peeked)(&game.notation.format(position), game.player_peeks));
                 }
-                break; //Exit after placing the ship
+                None if game.player_peeks == 0 => println!("{}", text.no_peeks_left),
+                None => println!("{}", text.nothing_to_peek),
+            }
+            continue;
+        }
+
+        if command == Some("history") {
+            for shot in &game.history {
+                println!("{}", game.format_shot(shot));
             }
+            continue;
+        }
+
+        if command == Some("save") && game.mode == GameMode::Network {
+            println!("{}", text.no_network_save);
+            continue;
+        }
+
+        if command == Some("save") && game.mode == GameMode::Teams {
+            println!("{}", text.no_team_save);
+            continue;
+        }
+
+        if command == Some("save") { //Optionally followed by a path
+            let path = words.next().unwrap_or(DEFAULT_SAVE_PATH);
+            match save_game(game, path) {
+                Ok(()) => println!("{}", (text.saved_to)(path)),
+                Err(e) => println!("{}: {}", text.save_failed, e),
+            }
+            continue;
+        }
+
+        match parse_coordinates(&input, rows, columns) {
+            //Someone typing at the keyboard may want to throw the shot away, a script surely does not
+            Ok(position) if already_fired(&game.computer_board, position) && io::stdin().is_terminal() => {
+                let answer = match read_line_raw(&(text.fire_anyway)(&game.notation.format(position)), deadline, &|| redraw(game, true)) {
+                    Line::Typed(answer) => answer,
+                    Line::TimeUp => return InputAction::TimeUp,
+                    Line::Closed => {
+                        println!("{}", text.input_closed);
+                        return InputAction::Quit;
+                    }
+                };
+                if text.yes.contains(&answer.trim().to_lowercase().as_str()) {
+                    return InputAction::Fire(position);
+                }
+            }
+            Ok(position) if already_fired(&game.computer_board, position) => {
+                println!("{}", (text.already_fired_at)(&game.notation.format(position)));
+            }
+            Ok(position) => return InputAction::Fire(position),
+            Err(e) => {
+                println!("{}", (text.parse_error)(&e));
+
+                let guidance = match e { //Tell the player how to fix it
+                    ParseError::Empty => text.guide_empty.to_string(),
+                    ParseError::WrongFieldCount(_) => text.guide_separator.to_string(),
+                    ParseError::NotANumber(_) => text.guide_numbers.to_string(),
+                    ParseError::OutOfRange { axis: Axis::Row, .. } => (text.guide_rows)(rows - 1),
+                    ParseError::OutOfRange { axis: Axis::Column, .. } => (text.guide_columns)(columns - 1),
+                    ParseError::LetterOutOfRange(_) => (text.guide_letters)(column_letter(columns - 1)),
+                };
+                println!("{}", guidance);
+            }
+        }
+    }
+}
+
+fn write_stats(game: &Game, winner: Option<Player>, path: Option<&str>) { //Only with --stats-out
+    let text = game.language.messages();
+    if let Some(path) = path {
+        match save_stats(game, winner, path) {
+            Ok(()) => println!("{}", (text.stats_written)(path)),
+            Err(e) => println!("{}: {}", text.stats_failed, e),
         }
     }
+}
+
+fn numbered_path(path: &str, number: usize) -> String { //Where the number-th game of a session writes, e.g. game-2.json, the first one uses the path as given
+    if number == 1 {
+        return path.to_string();
+    }
+    let path = Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, number, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, number),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+fn stats_side_by_side(game: &Game, player: &Stats, computer: &Stats) -> String {
+    let text = game.language.messages();
+    let player = (text.stats)(player);
+    let computer = (text.stats)(computer);
+
+    let mut table = format!("{:<20}{}\n", game.player_label(Player::Human), game.player_label(Player::Computer));
+    for (left, right) in player.lines().zip(computer.lines()) {
+        table.push_str(&format!("{:<20}{}\n", left, right));
+    }
+
+    table
+}
+
+fn same_layout(a: &Board, b: &Board) -> bool {
+    a.ships.len() == b.ships.len() && a.ships.iter().zip(&b.ships).all(|(x, y)| x.cells == y.cells)
+}
+
+fn unfired(board: &Board) -> Board { //The board as it was before the first shot
+    let mut board = board.clone();
+    for row in 0..board.rows {
+        for column in 0..board.columns {
+            board.undo_fire(Position { row, column }).expect("every row and column is on the board");
+        }
+    }
+    board
+}
+
+fn replay_game(path: &str, delay: Duration, language: Language, (palette, theme): (Option<Palette>, RenderTheme)) -> Result<(), LoadError> {
+    let saved = read_save(path)?;
+
+    //Random fleets come back from the seed, manually placed ones only from the recorded boards
+    let fleet = |board: &Board| -> Fleet { board.ships.iter().map(|ship| (ship.kind, ship.size)).collect() };
+    let fleets = (&fleet(&saved.player_board), &fleet(&saved.computer_board));
+    let regenerated = Game::with_fleets((saved.player_board.rows, saved.player_board.columns), saved.seed, saved.mode, saved.difficulty, saved.player_board.rules, fleets, PlayerPlacement::Random)
+        .ok()
+        .filter(|game| same_layout(&game.player_board, &saved.player_board) && same_layout(&game.computer_board, &saved.computer_board));
+    let mut game = match regenerated {
+        Some(game) => game,
+        None => {
+            let mut game = load_game(path)?;
+            game.player_board = unfired(&game.player_board);
+            game.computer_board = unfired(&game.computer_board);
+            game.history.clear();
+            game
+        }
+    };
+    game.computer_board.board_visibility = BoardVisibility::Visible; //Nothing left to hide
+    game.language = language;
+    game.player_board.palette = palette;
+    game.computer_board.palette = palette;
+    game.player_board.theme = theme;
+    game.computer_board.theme = theme;
+
+    let mut previous = None;
+    for &(player, position, _) in &saved.history {
+        let result = match player {
+            Player::Human => game.computer_board.fire(position),
+            Player::Computer => game.player_board.fire(position),
+        }.map_err(|OutOfBounds(position)| LoadError::ShotOffBoard(position))?;
+        if result == FireResult::AlreadyFired { //Water that a sinking shot already revealed
+            continue;
+        }
+        game.record_shot(player, position, result);
+
+        let stats = match player {
+            Player::Human => &mut game.player_stats,
+            Player::Computer => &mut game.computer_stats,
+        };
+        stats.record(result);
+        if previous != Some(player) { //A salvo is several shots in one turn
+            stats.turns += 1;
+        }
+        previous = Some(player);
+        game.turn = player; //The header names whoever fired this shot
+
+        redraw(&game, false);
+        thread::sleep(delay);
+    }
+
+    println!();
+    print!("{}", stats_side_by_side(&game, &game.player_stats, &game.computer_stats));
+    Ok(())
+}
 
-    fn can_place(&self, position: &Position, size: usize, orientation: Orientation) -> bool {
-        match orientation {
-            Orientation::Horizontal => {
-                if position.column + size > BOARD_SIZE {
+fn place_fleet_manually(board: &mut Board, fleet: &Fleet, text: &Messages) -> bool { //false if the input ran out first
+    let mut placed = None; //Where the last ship went, shown once the screen is cleared
+
+    for &(kind, size) in fleet {
+        loop {
+            let mut stdout = stdout();
+            execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+            stdout.flush().unwrap();
+
+            if let Some(placed) = placed.take() {
+                println!("{}", placed);
+            }
+            println!("{}", text.place_your_ships);
+            println!("{}", board);
+            println!("{}", (text.placing)((text.ship)(kind), size));
+
+            let position = loop {
+                print!("{}", text.start_prompt);
+                stdout.flush().unwrap();
+                let Some(input) = read_stdin() else {
                     return false;
+                };
+
+                match parse_coordinates(&input, board.rows, board.columns) {
+                    Ok(position) => break position,
+                    Err(e) => println!("{}", (text.parse_error)(&e)),
                 }
+            };
 
-                for i in 0..size {
-                    if self.grid[position.row][position.column + i] != CellState::Empty {
-                        return false;
-                    }
+            let (orientation, extend) = loop {
+                if board.rules.diagonal {
+                    print!("{}", text.orientation_prompt_diagonal);
+                } else {
+                    print!("{}", text.orientation_prompt);
                 }
-            }
-            Orientation::Vertical => {
-                if position.row + size > BOARD_SIZE {
+                stdout.flush().unwrap();
+                let Some(input) = read_stdin() else {
                     return false;
+                };
+
+                //A leading - runs the ship back from the cell, to the left or upwards
+                let input = input.trim().to_ascii_lowercase();
+                let (extend, input) = match input.strip_prefix('-') {
+                    Some(rest) => (Extend::Backward, rest.trim_start()),
+                    None => (Extend::Forward, input.as_str()),
+                };
+                match input {
+                    "h" | "horizontal" => break (Orientation::Horizontal, extend),
+                    "v" | "vertical" => break (Orientation::Vertical, extend),
+                    "d" | "down" if board.rules.diagonal => break (Orientation::DiagonalDown, extend),
+                    "u" | "up" if board.rules.diagonal => break (Orientation::DiagonalUp, extend),
+                    _ if board.rules.diagonal => println!("{}", text.orientation_help_diagonal),
+                    _ => println!("{}", text.orientation_help),
                 }
+            };
 
-                for i in 0..size {
-                    if self.grid[position.row + i][position.column] != CellState::Empty {
+            match board.place_ship_towards(kind, position, size, orientation, extend, true) {
+                Ok(cells) => {
+                    let cells: Vec<String> = cells.into_iter().map(|cell| Notation::Numeric.format(cell)).collect();
+                    placed = Some((text.placed_cells)((text.ship)(kind), &cells.join(" ")));
+                    break;
+                }
+                Err(e) => {
+                    println!("{} {}", (text.placement_error)(&e), text.try_again);
+                    if read_stdin().is_none() {
                         return false;
                     }
                 }
             }
         }
+    }
 
-        true
+    true
+}
+
+fn review_fleet(board: &mut Board, fleet: &Fleet, rng: &mut StdRng, text: &Messages) -> bool { //Until the random layout is kept, false if the input ran out first
+    loop {
+        let mut stdout = stdout();
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+        stdout.flush().unwrap();
+
+        println!("{}", text.your_fleet);
+        println!("{}", board);
+        print!("{}", text.keep_or_reshuffle);
+        stdout.flush().unwrap();
+        let Some(input) = read_stdin() else {
+            return false;
+        };
+
+        if !text.reshuffle.contains(&input.trim().to_lowercase().as_str()) {
+            return true;
+        }
+        if let Err(e) = board.reshuffle(fleet, rng) { //Very unlikely, the fleet fitted once already
+            println!("{}", (text.fleet_error)(&e));
+            return true;
+        }
     }
+}
 
-    fn fire(&mut self, position: Position) -> CellState {
-        match self.grid[position.row][position.column] {
-            CellState::Empty => {
-                self.grid[position.row][position.column] = CellState::Miss;
-                CellState::Miss
+fn dragged_ship(board: &Board, left: &Fleet, from: Position, to: Position) -> (Vec<Position>, Option<(usize, Position, Orientation)>) { //The cells covered, and which ship goes where if it fits
+    let Some((start, orientation, length)) = Orientation::between(from, to) else {
+        return (vec![from, to], None);
+    };
+    let cells = (0..length).filter_map(|i| orientation.cell(start, i)).collect();
+
+    let allowed = board.rules.diagonal || matches!(orientation, Orientation::Horizontal | Orientation::Vertical);
+    let ship = left.iter().position(|&(_, size)| size == length)
+        .filter(|_| allowed && board.can_place(&start, length, orientation));
+
+    (cells, ship.map(|index| (index, start, orientation)))
+}
+
+fn drag_fleet(board: &mut Board, fleet: &Fleet, text: &Messages) -> Fleet { //Returns the ships still to place if the player switched to typing
+    let mut left = fleet.clone();
+
+    let Some(_raw) = RawMode::enable() else { //No mouse without a terminal
+        return left;
+    };
+    execute!(stdout(), EnableMouseCapture).unwrap();
+
+    let mut from = None; //Where the current drag started
+    while !left.is_empty() {
+        let mut stdout = stdout();
+        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+        print!("{}\r\n", text.place_your_ships); //The board starts on the second line, mouse rows are counted from there
+        print!("{}", board.to_string().replace('\n', "\r\n"));
+        let ships: Vec<String> = left.iter().map(|&(kind, size)| format!("{} ({})", (text.ship)(kind), size)).collect();
+        print!("{}\r\n{}\r\n", (text.still_to_place)(&ships.join(", ")), text.drag_help);
+        stdout.flush().unwrap();
+
+        let event = match event::read() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+
+        match event {
+            Event::Mouse(mouse) => {
+                let cell = (mouse.row as usize).checked_sub(1).and_then(|y| board.cell_at(mouse.column as usize, y));
+
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        from = cell;
+                        board.preview = cell.map(|cell| (vec![cell], true));
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        if let (Some(from), Some(to)) = (from, cell) { //Keep the last good preview while outside the board
+                            let (cells, ship) = dragged_ship(board, &left, from, to);
+                            board.preview = Some((cells, ship.is_some()));
+                        }
+                    }
+                    MouseEventKind::Up(MouseButton::Left) => {
+                        let Some(start) = from.take() else {
+                            continue;
+                        };
+
+                        let placed = match cell.map(|to| dragged_ship(board, &left, start, to)) {
+                            Some((_, Some((index, position, orientation)))) => {
+                                let (kind, size) = left[index];
+                                let placed = board.place_ship_at(kind, position, size, orientation).is_ok();
+                                if placed {
+                                    left.remove(index);
+                                }
+                                placed
+                            }
+                            _ => false, //Dropped off the board, or where no ship fits
+                        };
+
+                        if !placed {
+                            if let Some((cells, _)) = board.preview.take() {
+                                board.preview = Some((cells, false));
+                                execute!(stdout, MoveTo(0, 1)).unwrap();
+                                print!("{}", board.to_string().replace('\n', "\r\n"));
+                                stdout.flush().unwrap();
+                                thread::sleep(Duration::from_millis(FLASH_MS));
+                            }
+                        }
+                        board.preview = None;
+                    }
+                    _ => (),
+                }
             }
-            CellState::Ship => {
-                self.grid[position.row][position.column] = CellState::Hit;
-                CellState::Hit
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    restore_terminal(); //Exiting skips the guard
+                    std::process::exit(130); //Raw mode swallows the usual interrupt
+                }
+                KeyCode::Esc => break,
+                _ => ()
+            },
+            _ => (),
+        }
+    }
+
+    board.preview = None;
+    left //The guard lets go of the mouse and the terminal
+}
+
+fn set_up_fleet(board: &mut Board, fleet: &Fleet, drag: bool, text: &Messages) -> bool { //false if the input ran out first
+    let left = if drag { drag_fleet(board, fleet, text) } else { fleet.clone() };
+    place_fleet_manually(board, &left, text)
+}
+
+enum TurnEnd { //How a player's turn finished
+    Next, //Over to the other side
+    Again, //The turn was taken back and is played again
+    Chained, //Hit in a chain game, the same side fires once more
+    Won,
+    Quit,
+    Surrendered, //Gave up, the enemy fleet is shown
+}
+
+fn human_turn(game: &mut Game, cursor_mode: bool) -> TurnEnd { //The shooter's own board is player_board
+    redraw(game, false);
+
+    let text = game.language.messages();
+    let palette = game.player_board.palette;
+    let shooter = game.turn;
+    let shots = game.mode.shots(&game.player_board);
+    let tallied = (game.player_stats.hits, game.player_stats.misses); //Before the salvo, what an undo takes back drops out again
+
+    let outcome = loop {
+        if shots > 1 {
+            println!("{}", (text.shot_of)(game.salvo_fired + 1, shots));
+        }
+
+        let asked = Instant::now();
+        let action = if cursor_mode { cursor_input(game) } else { user_input(game, game.turn_limit) };
+        match action {
+            InputAction::Fire(_) | InputAction::Auto => game.player_stats.think_times.push(asked.elapsed()),
+            InputAction::Scan(_, _) if game.salvo_fired > 0 => {
+                println!("{}", text.scan_instead_of_salvo);
+                continue;
+            }
+            InputAction::TimeUp => println!("{}", tint(text.time_up_turn, palette, |palette| palette.warning)),
+            _ => (),
+        }
+
+        let outcome = game.step(action);
+        if let Some(e) = outcome.off_board { //Input is only taken for cells of the board, so this would be a bug
+            println!("{}", (text.out_of_bounds)(&e));
+        }
+        if let Some(position) = outcome.undone {
+            redraw(game, false);
+            println!("{}", (text.took_back)(&game.notation.format(position)));
+        }
+        for &(_, position, result) in &outcome.shots {
+            if action == InputAction::Auto {
+                println!("{}", (text.auto_firing)(&game.notation.format(position)));
+            }
+            match result {
+                FireResult::Hit => println!("{}", tint(text.you_hit, palette, |palette| palette.hit)),
+                FireResult::Miss => {
+                    println!("{}", tint(text.you_missed, palette, |palette| palette.miss));
+                    match outcome.distance {
+                        Some(distance) if game.assist && distance <= WARM_DISTANCE => println!("{}", tint(text.miss_warm, palette, |palette| palette.hit)),
+                        Some(distance) if game.assist && distance >= COLD_DISTANCE => println!("{}", tint(text.miss_cold, palette, |palette| palette.water)),
+                        _ => (),
+                    }
+                }
+                FireResult::Sunk(kind) => println!("{}", shout(&(text.you_sank)((text.ship)(kind)), palette)),
+                FireResult::Mine => println!("{}", tint(text.you_hit_mine, palette, |palette| palette.warning)),
+                FireResult::AlreadyFired => println!("{}", text.you_already_fired),
+            }
+        }
+        if let (InputAction::Scan(axis, index), Some(found)) = (action, outcome.scanned) {
+            let line = match axis {
+                Axis::Row => (text.scan_row)(index),
+                Axis::Column => (text.scan_column)(&game.computer_board.column_label(index)),
+            };
+            println!("{}", (text.scan_result)(&line, found, game.player_scans));
+            pause(game, text.press_enter);
+            return if outcome.over { TurnEnd::Won } else { TurnEnd::Next }; //Takes the whole turn and cannot be undone
+        }
+
+        match (outcome.over, outcome.winner) {
+            (true, None) => return TurnEnd::Quit,
+            (true, Some(winner)) if winner != shooter => return TurnEnd::Surrendered,
+            _ => (),
+        }
+        if outcome.over || outcome.turn != shooter {
+            break outcome; //Fired the whole turn
+        }
+        if !outcome.shots.is_empty() && game.salvo_fired == 0 { //A hit in a chain game, the same side fires once more
+            show_banner(game, true);
+            println!("{}", text.fire_again);
+            return TurnEnd::Chained;
+        }
+    };
+    show_banner(game, true);
+
+    if shots > 1 {
+        println!("{}", (text.salvo_tally)(game.player_stats.hits - tallied.0, game.player_stats.misses - tallied.1));
+    }
+    if game.queued.is_empty() || game.all_sunk(Player::Computer) { //The burst is spent, or nothing is left to fire at
+        game.queued.clear();
+        if let Some((hits, misses)) = game.burst.take() {
+            println!("{}", (text.burst_tally)(game.player_stats.hits.saturating_sub(hits), game.player_stats.misses.saturating_sub(misses)));
+        }
+    }
+
+    if pause(game, text.continue_or_undo).trim() == "undo" {
+        while let Some(position) = game.step(InputAction::Undo).undone { //Every shot of the turn, also those before a chained hit
+            println!("{}", (text.took_back)(&game.notation.format(position)));
+        }
+        return TurnEnd::Again;
+    }
+
+    if outcome.over { TurnEnd::Won } else { TurnEnd::Next }
+}
+
+fn choose_enemy_board(game: &Game) -> Option<usize> { //Team games: which enemy board to fire at, None to quit
+    let text = game.language.messages();
+    let boards = game.boards(Player::Computer);
+    let afloat: Vec<usize> = (0..boards.len()).filter(|&index| !boards[index].game_over()).collect();
+    if let [only] = afloat[..] { //Nothing to choose
+        return Some(only);
+    }
+
+    loop {
+        let input = match read_line_raw(&(text.which_board)(boards.len()), None, &|| redraw(game, true)) {
+            Line::Typed(input) => input,
+            Line::TimeUp | Line::Closed => { //No deadline, so the input ran out
+                println!("{}", text.input_closed);
+                return None;
             }
-            _ => CellState::Miss
+        };
+
+        let choice = input.trim();
+        if matches!(choice, "quit" | "q") {
+            return None;
+        }
+
+        match choice.parse::<usize>() {
+            Ok(number) if number > 0 && afloat.contains(&(number - 1)) => return Some(number - 1),
+            Ok(number) if (1..=boards.len()).contains(&number) => println!("{}", (text.board_already_sunk)(number)),
+            _ => println!("{}", (text.which_board_help)(boards.len())),
         }
     }
+}
 
-    fn game_over(&self) -> bool {
-        //If all the squares are hit, the game is over
-        self.ships.iter().all(
-            |&position| self.grid[position.row][position.column] == CellState::Hit
-        )
+fn surrender(game: &mut Game, player: Player) { //Show where everything was, then the game is lost
+    for board in game.boards_mut() {
+        board.board_visibility = BoardVisibility::Visible;
     }
+    redraw(game, false);
+
+    let text = game.language.messages();
+    if game.mode == GameMode::Hotseat {
+        println!("{}", (text.player_surrendered)(&game.player_label(player)));
+    } else {
+        println!("{}", text.you_surrendered);
+    }
+}
+
+enum Message { //One line of the network protocol
+    Hello { rows: usize, columns: usize, fleet: String, rules: String }, //Both sides must agree on all of it
+    Fire(Position),
+    Result(FireResult),
+    GameOver, //Follows a sinking result when no ships are left
+    PlayOn, //Follows a sinking result when ships are left
+    Pass, //The player ran out of time and does not fire
 }
 
-impl Display for Board {
+impl Display for Message {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "   ")?;
+        match self {
+            Message::Hello { rows, columns, fleet, rules } => write!(f, "HELLO {} {} {} {}", rows, columns, fleet, rules),
+            Message::Fire(position) => write!(f, "FIRE {} {}", position.row, position.column),
+            Message::Result(FireResult::Hit) => write!(f, "RESULT HIT"),
+            Message::Result(FireResult::Miss) => write!(f, "RESULT MISS"),
+            Message::Result(FireResult::Sunk(kind)) => write!(f, "RESULT SUNK {}", kind),
+            Message::Result(FireResult::Mine) => write!(f, "RESULT MINE"),
+            Message::Result(FireResult::AlreadyFired) => write!(f, "RESULT ALREADY"),
+            Message::GameOver => write!(f, "GAMEOVER"),
+            Message::PlayOn => write!(f, "PLAYON"),
+            Message::Pass => write!(f, "PASS"),
+        }
+    }
+}
 
-        for i in 0..BOARD_SIZE { //Column Numbers
-            write!(f, " {} ", i)?;
+fn parse_message(line: &str) -> Option<Message> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    match words[..] {
+        ["HELLO", rows, columns, fleet, rules] => Some(Message::Hello { rows: rows.parse().ok()?, columns: columns.parse().ok()?, fleet: fleet.to_string(), rules: rules.to_string() }),
+        ["FIRE", row, column] => Some(Message::Fire(Position { row: row.parse().ok()?, column: column.parse().ok()? })),
+        ["RESULT", "HIT"] => Some(Message::Result(FireResult::Hit)),
+        ["RESULT", "MISS"] => Some(Message::Result(FireResult::Miss)),
+        ["RESULT", "MINE"] => Some(Message::Result(FireResult::Mine)),
+        ["RESULT", "ALREADY"] => Some(Message::Result(FireResult::AlreadyFired)),
+        ["RESULT", "SUNK", name] => NAVY_FLEET.iter()
+            .find(|(kind, _)| kind.to_string() == name)
+            .map(|&(kind, _)| Message::Result(FireResult::Sunk(kind))),
+        ["GAMEOVER"] => Some(Message::GameOver),
+        ["PLAYON"] => Some(Message::PlayOn),
+        ["PASS"] => Some(Message::Pass),
+        _ => None,
+    }
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    text: &'static Messages, //For describing what went wrong
+}
+
+impl Connection {
+    fn new(stream: TcpStream, text: &'static Messages) -> io::Result<Self> {
+        Ok(Connection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            text,
+        })
+    }
+
+    fn send(&mut self, message: &Message) -> io::Result<()> {
+        writeln!(self.writer, "{}", message)
+    }
+
+    fn receive(&mut self) -> Result<Message, String> { //Err describes why the game cannot go on
+        let mut line = String::new();
+
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err(self.text.disconnected.to_string()),
+            Ok(_) => parse_message(&line).ok_or_else(|| (self.text.unexpected_message)(line.trim())),
+            Err(e) => Err(format!("{}: {}", self.text.lost_connection, e)),
         }
-        writeln!(f)?;
+    }
+}
 
-        for (i, row) in self.grid.iter().enumerate() {
-            write!(f, "{:2}", i)?;
-            for cell in row {
-                match cell {
-                    CellState::Empty => {
-                        if matches!(self.board_visibility, BoardVisibility::Hidden) {
-                            write!(f, "   ")?;
-                        } else {
-                            write!(f, " \u{25A1} ")?;
-                        }
+fn connect(host: Option<u16>, address: Option<String>, text: &'static Messages) -> io::Result<(Connection, bool)> { //true if we are the host
+    match (host, address) {
+        (Some(port), _) => {
+            let listener = TcpListener::bind(("0.0.0.0", port))?;
+            println!("{}", (text.waiting_on_port)(port));
+            let (stream, peer) = listener.accept()?;
+            println!("{}", (text.connected)(&peer.to_string()));
+            Ok((Connection::new(stream, text)?, true))
+        }
+        (None, Some(address)) => Ok((Connection::new(TcpStream::connect(address)?, text)?, false)),
+        (None, None) => Err(io::Error::other(text.host_or_connect)),
+    }
+}
+
+fn setup(board: &Board) -> (String, String) { //The fleet and rules the other side must play with too, e.g. Destroyer:2,Cruiser:3 and touching:straight:0
+    let fleet: Vec<String> = board.ships.iter().map(|ship| format!("{}:{}", ship.kind, ship.size)).collect();
+    let adjacency = match board.rules.adjacency {
+        AdjacencyRule::Touching => "touching",
+        AdjacencyRule::Corners => "corners",
+        AdjacencyRule::NoTouch => "no-touch",
+    };
+    let rules = format!("{}:{}:{}", adjacency, if board.rules.diagonal { "diagonal" } else { "straight" }, board.rules.mines);
+    (fleet.join(","), rules)
+}
+
+fn network_game(game: &mut Game, connection: &mut Connection, host: bool, cursor_mode: bool) -> Result<Player, String> { //Returns the winner
+    let text = game.language.messages();
+    let network_error = |e: io::Error| format!("{}: {}", text.lost_connection, e);
+
+    let (rows, columns) = (game.player_board.rows, game.player_board.columns);
+    let (fleet, rules) = setup(&game.player_board);
+    connection.send(&Message::Hello { rows, columns, fleet: fleet.clone(), rules: rules.clone() }).map_err(network_error)?;
+    match connection.receive()? {
+        Message::Hello { rows: their_rows, columns: their_columns, .. } if (their_rows, their_columns) != (rows, columns) => {
+            return Err((text.board_mismatch)(their_rows, their_columns));
+        }
+        Message::Hello { fleet: theirs, .. } if theirs != fleet => return Err((text.fleet_mismatch)(&theirs)),
+        Message::Hello { rules: theirs, .. } if theirs != rules => return Err((text.rules_mismatch)(&theirs)),
+        Message::Hello { .. } => (),
+        _ => return Err(text.no_hello.to_string()),
+    }
+
+    game.turn = if host { Player::Human } else { Player::Computer }; //The host fires first
+
+    loop {
+        redraw(game, false);
+
+        match game.turn {
+            Player::Human => {
+                if game.skip_turn == Some(Player::Human) { //We set off a mine last turn
+                    game.skip_turn = None;
+                    connection.send(&Message::Pass).map_err(network_error)?;
+                    game.turn = Player::Computer;
+                    continue;
+                }
+
+                game.player_stats.turns += 1;
+                let asked = Instant::now();
+                let action = if cursor_mode { cursor_input(game) } else { user_input(game, game.turn_limit) };
+                let target = match action {
+                    InputAction::Fire(position) => {
+                        game.player_stats.think_times.push(asked.elapsed());
+                        position
                     }
-                    CellState::Ship => {
-                        if matches!(self.board_visibility, BoardVisibility::Hidden) {
-                            write!(f, "   ")?;
-                        } else {
-                            write!(f, " \u{25A0} ")?;
-                        }
+                    InputAction::Queued(position) => position,
+                    InputAction::Scan(..) | InputAction::Auto | InputAction::Undo => continue, //Network games have no scans to spend, nor a fleet to guess at or shots kept to take back
+                    InputAction::Quit | InputAction::Surrender => return Err(text.you_left.to_string()), //The opponent's fleet is not known here
+                    InputAction::TimeUp => {
+                        println!("{}", tint(text.time_up_shot, game.player_board.palette, |palette| palette.warning));
+                        connection.send(&Message::Pass).map_err(network_error)?;
+                        game.turn = Player::Computer;
+                        continue;
+                    }
+                };
+
+                connection.send(&Message::Fire(target)).map_err(network_error)?;
+                let result = match connection.receive()? {
+                    Message::Result(result) => result,
+                    _ => return Err(text.no_answer.to_string()),
+                };
+
+                //The opponent's fleet is not known here, so mark the answer directly
+                let marked = match result {
+                    FireResult::Miss => Some(CellState::Miss),
+                    FireResult::Mine => Some(CellState::Detonated),
+                    FireResult::Hit | FireResult::Sunk(_) => Some(CellState::Hit),
+                    FireResult::AlreadyFired => None, //Nothing new to mark
+                };
+                match marked {
+                    Some(state) => {
+                        game.computer_board.set_cell(target, state);
+                    }
+                    None => println!("{}", text.you_already_fired),
+                }
+                game.record_shot(Player::Human, target, result);
+                game.player_stats.record(result);
+
+                show_banner(game, true);
+                match result {
+                    FireResult::Sunk(kind) => println!("{}", shout(&(text.you_sank)((text.ship)(kind)), game.player_board.palette)),
+                    FireResult::Mine => {
+                        println!("{}", tint(text.you_hit_mine, game.player_board.palette, |palette| palette.warning));
+                        game.skip_turn = Some(Player::Human);
+                    }
+                    _ => (),
+                }
+
+                if matches!(result, FireResult::Sunk(_)) { //Only a sinking shot can end the game
+                    match connection.receive()? {
+                        Message::GameOver => return Ok(Player::Human),
+                        Message::PlayOn => (),
+                        _ => return Err(text.no_game_over_word.to_string()),
                     }
-                    CellState::Hit => write!(f, " {} ", "\u{25CF}".red())?,
-                    CellState::Miss => write!(f, " {} ", "\u{25CF}".blue())?
                 }
+                game.turn = Player::Computer;
             }
-            writeln!(f)?;
-        }
+            Player::Computer => {
+                println!("{}", text.waiting_for_shot);
+                game.computer_stats.turns += 1;
+
+                let target = match connection.receive()? {
+                    Message::Fire(position) => position,
+                    Message::Pass => {
+                        game.turn = Player::Human;
+                        continue;
+                    }
+                    _ => return Err(text.did_not_fire.to_string()),
+                };
 
-        Ok(())
+                let result = game.player_board.fire(target)
+                    .map_err(|OutOfBounds(position)| (text.fired_off_board)(position.row, position.column))?;
+                connection.send(&Message::Result(result)).map_err(network_error)?;
+                game.record_shot(Player::Computer, target, result);
+                game.computer_stats.record(result);
+
+                if matches!(result, FireResult::Sunk(_)) {
+                    if game.player_board.game_over() {
+                        connection.send(&Message::GameOver).map_err(network_error)?;
+                        return Ok(Player::Computer);
+                    }
+                    connection.send(&Message::PlayOn).map_err(network_error)?;
+                }
+                game.turn = Player::Human;
+            }
+        }
     }
 }
 
-fn user_input() -> Position {
-    loop {
-        print!("Enter the coordinates to fire to (row, column): ");
-        stdout().flush().unwrap();
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read coordinates");
+fn bench((games, seed): (usize, u64), board_size: (usize, usize), mode: GameMode, rules: Rules, fleets: (&Fleet, &Fleet), sides: [Difficulty; 2], text: &Messages) -> Result<(), FleetError> { //Side A has the player's fleet, B the computer's
+    let mut wins = [0; 2];
+    let mut turns_to_win = [0; 2];
+    let mut unfinished = 0;
 
-        match parse_coordinates(&input) {
-            Ok(position) => {
-                if position.row < BOARD_SIZE && position.column < BOARD_SIZE {
-                    return position;
-                }
+    for game_seed in (0..games as u64).map(|i| seed.wrapping_add(i)) {
+        let mut game = Game::with_fleets(board_size, game_seed, mode, sides[1], rules, fleets, PlayerPlacement::Random)?;
+        let mut challenger = sides[0].strategy(StdRng::seed_from_u64(!game_seed), AiState::default());
+        if game_seed % 2 == 1 { //Take turns firing first, so neither side gets the edge
+            game.turn = Player::Computer;
+        }
+
+        match game.play_out(challenger.as_mut()) {
+            Some(Player::Human) => {
+                wins[0] += 1;
+                turns_to_win[0] += game.player_stats.turns;
             }
-            Err(e) => {
-                println!("{}", e);
+            Some(Player::Computer) => {
+                wins[1] += 1;
+                turns_to_win[1] += game.computer_stats.turns;
             }
+            None => unfinished += 1,
+        }
+    }
+
+    let [side, won, rate, turns] = text.bench_columns;
+    println!("{}", (text.bench_games)(games, seed));
+    println!("{:<14}{:>8}{:>10}{:>18}", side, won, rate, turns);
+    for (i, (label, difficulty)) in ["A", "B"].iter().zip(sides).enumerate() {
+        let name = difficulty.to_possible_value().map(|value| value.get_name().to_string()).unwrap_or_default();
+        let average = if wins[i] == 0 { 0.0 } else { turns_to_win[i] as f64 / wins[i] as f64 };
+        println!("{:<14}{:>8}{:>9.1}%{:>18.1}", format!("{}: {}", label, name), wins[i], wins[i] as f64 * 100.0 / games.max(1) as f64, average);
+    }
+    if unfinished > 0 {
+        println!("{}", (text.bench_unfinished)(unfinished));
+    }
+
+    Ok(())
+}
+
+fn audit((runs, seed): (usize, u64), board_size: (usize, usize), rules: Rules, fleet: &Fleet, text: &Messages) -> Result<(), FleetError> {
+    let counts = placement_audit(board_size, rules, fleet, (runs, seed))?;
+    let width = counts.iter().flatten().max().map_or(1, |most| most.to_string().len()).max(board_size.1.to_string().len()) + 1;
+    let label_width = board_size.0.to_string().len();
+
+    println!("{}", (text.audit_layouts)(runs, seed));
+    print!("{:width$}", "", width = label_width);
+    for column in 0..board_size.1 {
+        print!("{:>width$}", column, width = width);
+    }
+    println!();
+    for (row, line) in counts.iter().enumerate() {
+        print!("{:>width$}", row, width = label_width);
+        for count in line {
+            print!("{:>width$}", count, width = width);
         }
+        println!();
     }
+
+    let fewest = counts.iter().flatten().min().copied().unwrap_or(0);
+    let most = counts.iter().flatten().max().copied().unwrap_or(0);
+    println!("{}", (text.audit_range)(fewest, most));
+
+    Ok(())
 }
 
-fn parse_coordinates(input: &str) -> Result<Position, &'static str> { //Can create an error Enum
-    let mut coords = input.trim().split(',')
-        .map(|c| c.trim().parse());
+fn pause(game: &Game, prompt: &str) -> String { //Wait for Enter between turns unless told not to, returns what was typed
+    if !game.pause {
+        return String::new();
+    }
 
-    if let (Some(Ok(row)), Some(Ok(column)), None) = (coords.next(), coords.next(), coords.next()) {
-        Ok(Position { row, column })
-    } else {
-        Err("Invalid input. Please enter coordinates in the form of (row, column).")
+    println!("{}", prompt);
+    read_stdin().unwrap_or_default() //Without more input the next prompt ends the game
+}
+
+fn pass_device(player: &str, text: &Messages) { //Blank screen so the next player cannot see the previous player's fleet
+    let mut stdout = stdout();
+    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
+    println!("{}", (text.pass_device)(player));
+    stdout.flush().unwrap();
+    read_stdin();
+}
+
+fn parse_board_size(input: &str, text: &Messages) -> Result<(usize, usize), String> { //"10" is a square board, "8x12" has 8 rows and 12 columns
+    let dimension = |value: &str| match value.trim().parse() {
+        Ok(value) if (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&value) => Ok(value),
+        _ => Err((text.board_dimensions)(MIN_BOARD_SIZE, MAX_BOARD_SIZE)),
+    };
+
+    match input.split_once(['x', 'X']) {
+        Some((rows, columns)) => Ok((dimension(rows)?, dimension(columns)?)),
+        None => dimension(input).map(|size| (size, size)),
     }
 }
 
-fn opponent_move() -> Position { //Play a random move from the computer
-    let mut rng = rand::thread_rng();
-    Position { row: rng.gen_range(0..BOARD_SIZE), column: rng.gen_range(0..BOARD_SIZE) }
+#[derive(Parser)]
+#[command(about = "Battleship in the terminal")]
+struct Cli {
+    #[arg(long, value_name = "SIZE|ROWSxCOLUMNS", help = format!("Board size, e.g. 10 or 8x12 [default: {}]", DEFAULT_BOARD_SIZE))]
+    size: Option<String>, //Checked by board_size, once --lang is known
+    #[arg(long, help = "classic, navy, or ship names or sizes separated by commas [default: classic]")]
+    fleet: Option<String>,
+    #[arg(long, value_name = "FLEET", conflicts_with_all = ["load", "host", "connect"], help = "Your own fleet, or side A's in --bench games, in the same form as --fleet [default: --fleet]")]
+    player_fleet: Option<String>,
+    #[arg(long, value_name = "FLEET", conflicts_with_all = ["load", "host", "connect"], help = "The computer's fleet, or the second player's in hotseat games and side B's in --bench games [default: --fleet]")]
+    ai_fleet: Option<String>,
+    #[arg(long, value_enum, default_value_t = GameMode::Classic)]
+    mode: GameMode,
+    #[arg(long, value_enum, default_value_t = Difficulty::Medium)]
+    difficulty: Difficulty,
+    #[arg(long, help = "Seed for ship placement and AI moves [default: random]")]
+    seed: Option<u64>,
+    #[arg(long, conflicts_with_all = ["seed", "load"], help = "Seed the game from today's date, so everyone gets the same fleets and AI today")]
+    daily: bool,
+    #[arg(long, value_name = "GAMES", help = "Play this many games of --versus against --difficulty without any display, then print a summary")]
+    bench: Option<usize>,
+    #[arg(long, value_name = "LAYOUTS", conflicts_with_all = ["bench", "player_fleet", "ai_fleet"], help = "Place the --fleet this many times from consecutive seeds, then print how often each cell held a ship")]
+    placement_audit: Option<usize>,
+    #[arg(long, value_enum, value_name = "DIFFICULTY", help = "The other side in --bench games [default: the same as --difficulty]")]
+    versus: Option<Difficulty>,
+    #[arg(long, group = "spacing", help = "Ships may not touch, not even diagonally")]
+    no_touch: bool,
+    #[arg(long, group = "spacing", help = "Ships may meet at their corners, but never lie side by side")]
+    corners: bool,
+    #[arg(long, requires = "spacing", conflicts_with_all = ["host", "connect"], help = "Sinking a ship also reveals the water around it")]
+    reveal_sunk: bool,
+    #[arg(long, help = "Ships may also lie diagonally")]
+    diagonal: bool,
+    #[arg(long, help = "Show nothing of the enemy board, not even misses, until the first hit")]
+    fog: bool,
+    #[arg(long, value_enum, default_value_t = PlacementStrategy::Random, help = "How random fleets are laid out")]
+    placement: PlacementStrategy,
+    #[arg(long, value_name = "COUNT", default_value_t = 0, help = "Hide mines in each board that cost the shooter their next turn")]
+    mines: usize,
+    #[arg(long, conflicts_with_all = ["host", "connect"], help = "Say whether a miss was warm or cold, close to a ship or far from all of them")]
+    assist: bool,
+    #[arg(long, conflicts_with_all = ["host", "connect"], help = "Allow the auto command, which fires where the Hard computer would")]
+    auto_fire: bool,
+    #[arg(long, value_name = "CHARGES", default_value_t = 0, conflicts_with_all = ["host", "connect"], help = "Scans to fire at a whole row or column, which only tell how many cells hit")]
+    scans: usize,
+    #[arg(long, value_name = "CHARGES", default_value_t = 0, conflicts_with_all = ["host", "connect"], help = "Peeks for both sides, each uncovers one enemy ship cell without firing")]
+    peeks: usize,
+    #[arg(long, help = "Place your own fleet to defend, the end of the game tells how well it held")]
+    manual: bool,
+    #[arg(long, help = "Place your own fleet by dragging each ship with the mouse")]
+    drag: bool,
+    #[arg(long, conflicts_with_all = ["manual", "drag", "load", "host", "connect"], help = "Look at your random fleet and reshuffle it until you keep it")]
+    review: bool,
+    #[arg(long, value_enum, default_value_t = Notation::Numeric)]
+    notation: Notation,
+    #[arg(long, value_enum, default_value_t = DisplayStyle::Compact)]
+    style: DisplayStyle,
+    #[arg(long, value_enum, default_value_t = ColorScheme::Standard)]
+    colors: ColorScheme,
+    #[arg(long, value_enum, default_value_t = GlyphSet::Unicode, help = "Characters the boards are drawn with")]
+    glyphs: GlyphSet,
+    #[arg(long, help = "Plain glyphs without colour, also the default when not writing to a terminal")]
+    no_color: bool,
+    #[arg(long, value_enum, default_value_t = Language::English)]
+    lang: Language,
+    #[arg(long, help = "Outline the latest three shots at each board, fading with age")]
+    recent: bool,
+    #[arg(long, help = "Keep the result of the last shot in a banner at the top right")]
+    banner: bool,
+    #[arg(long, help = "Go straight on after each turn instead of waiting for Enter, the fast command toggles it in game")]
+    no_pause: bool,
+    #[arg(long, help = "Aim with the arrow keys instead of typing coordinates")]
+    cursor: bool,
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..), help = "Time to type each shot")]
+    turn_time: Option<u64>,
+    #[arg(long, value_name = "TURNS", value_parser = clap::value_parser!(u64).range(1..), conflicts_with_all = ["host", "connect"], help = "Call the game after each side had this many turns, the side with more ship cells afloat wins [default: unlimited]")]
+    turn_limit: Option<u64>,
+    #[arg(long, value_name = "PATH", help = "Resume a saved game")]
+    load: Option<String>,
+    #[arg(long, value_name = "PATH", help = "Save the finished game for --replay, later games of the session add -2, -3 and so on to the name")]
+    record: Option<String>,
+    #[arg(long, value_name = "PATH", help = "Write the final statistics as JSON, e.g. to compare many games, numbered like --record")]
+    stats_out: Option<String>,
+    #[arg(long, value_name = "PATH", help = "Keep the lifetime record of games against the computer here [default: battleship/record.json in the config directory]")]
+    profile: Option<String>,
+    #[arg(long, conflicts_with_all = ["host", "connect"], help = "Print both boards in plain text at the end, with every ship, hit and miss")]
+    final_boards: bool,
+    #[arg(long, value_name = "PATH", help = "Play back a recorded game")]
+    replay: Option<String>,
+    #[arg(long, value_name = "MS", default_value_t = DEFAULT_REPLAY_DELAY_MS, help = "Pause between replayed moves")]
+    speed: u64,
+    #[arg(long, help = "Enable the reveal and heatmap commands, which show the enemy fleet and where it could still be")]
+    debug: bool,
+    #[arg(long, value_name = "PORT", conflicts_with = "connect", help = "Wait for a network opponent")]
+    host: Option<u16>,
+    #[arg(long, value_name = "ADDR", help = "Join a network opponent, e.g. 127.0.0.1:4000")]
+    connect: Option<String>,
+}
+
+impl Cli {
+    fn board_size(&self) -> (usize, usize) { //--size, or the default square board, exits on a size that is not one
+        match &self.size {
+            Some(size) => parse_board_size(size, self.lang.messages()).unwrap_or_else(|e| usage_error(e)),
+            None => (DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE),
+        }
+    }
 }
 
+fn usage_error(message: impl Display) -> ! { //Print the message with usage and exit non-zero
+    Cli::command().error(ErrorKind::ValueValidation, message).exit()
+}
+
+fn default_profile_path() -> Option<String> { //Where the usual config directory of the platform is, if anywhere
+    let env = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(std::path::PathBuf::from);
+    let config = env("XDG_CONFIG_HOME")
+        .or_else(|| env("HOME").map(|home| home.join(".config")))
+        .or_else(|| env("APPDATA"))?; //Windows
+    config.join("battleship").join("record.json").to_str().map(String::from)
+}
+
+fn restore_terminal() { //Undo whatever raw input left behind, the game never switches to the alternate screen
+    let _ = terminal::disable_raw_mode();
+    if stdout().is_terminal() {
+        let _ = execute!(stdout(), DisableMouseCapture, Show);
+    }
+}
+
+struct RawMode; //Raw input for as long as it lives, restored even when a panic unwinds past it
+
+impl RawMode {
+    fn enable() -> Option<RawMode> { //None if this is not a real terminal
+        terminal::enable_raw_mode().ok().map(|_| RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn new_game(cli: &Cli, load: Option<&str>, seed: u64, (board_size, mode, rules, player_placement): ((usize, usize), GameMode, Rules, PlayerPlacement), (fleet, other_fleet): (&Fleet, &Fleet), palette: Option<Palette>) -> Option<Game> { //Set up from the command line, None if the input ran out while placing ships
+    let text = cli.lang.messages();
+
+    let mut game = match load {
+        Some(path) => load_game(path).unwrap_or_else(|e| {
+            eprintln!("{}", (text.load_error)(&e));
+            std::process::exit(1);
+        }),
+        None => {
+            let mut game = Game::with_fleets(board_size, seed, mode, cli.difficulty, rules, (fleet, other_fleet), player_placement)
+                .unwrap_or_else(|e| usage_error((text.fleet_error)(&e)));
+            game.language = cli.lang; //Needed before the game starts, for placing ships
+            for board in [&mut game.player_board, &mut game.computer_board] {
+                board.palette = palette;
+                board.theme = cli.glyphs.theme();
+            }
+            game.player_scans = cli.scans;
+            game.computer_scans = cli.scans; //Only a second human uses them
+            game.player_peeks = cli.peeks;
+            game.computer_peeks = cli.peeks;
+
+            if let PlayerPlacement::Manual = player_placement {
+                if mode == GameMode::Hotseat {
+                    pass_device(&game.player_label(Player::Human), text);
+                }
+                if !set_up_fleet(&mut game.player_board, fleet, cli.drag, text) {
+                    println!("{}", text.input_closed);
+                    return None;
+                }
+                game.player_board.place_mines(rules.mines, &mut game.rng);
+
+                if mode == GameMode::Hotseat {
+                    game.swap_sides();
+                    pass_device(&game.player_label(Player::Computer), text);
+                    if !set_up_fleet(&mut game.player_board, other_fleet, cli.drag, text) {
+                        println!("{}", text.input_closed);
+                        return None;
+                    }
+                    game.player_board.place_mines(rules.mines, &mut game.rng);
+                    game.swap_sides();
+                }
+            } else if cli.review && io::stdin().is_terminal() { //A script has no eyes to look at the layout
+                if mode == GameMode::Hotseat {
+                    pass_device(&game.player_label(Player::Human), text);
+                }
+                for board in std::iter::once(&mut game.player_board).chain(&mut game.player_boards) { //Every board in a team game
+                    if !review_fleet(board, fleet, &mut game.rng, text) {
+                        println!("{}", text.input_closed);
+                        return None;
+                    }
+                }
+
+                if mode == GameMode::Hotseat {
+                    game.swap_sides();
+                    pass_device(&game.player_label(Player::Computer), text);
+                    if !review_fleet(&mut game.player_board, other_fleet, &mut game.rng, text) {
+                        println!("{}", text.input_closed);
+                        return None;
+                    }
+                    game.swap_sides();
+                }
+            }
+            game
+        }
+    };
+    game.notation = cli.notation;
+    game.language = cli.lang;
+    game.turn_limit = cli.turn_time.map(Duration::from_secs);
+    game.debug = cli.debug;
+    game.assist = cli.assist;
+    game.auto_fire = cli.auto_fire;
+    game.pause = !cli.no_pause;
+    game.hold_reply = true; //The session loop plays the other side, so a turn can be taken back before it answers
+    game.max_turns = cli.turn_limit.map(|turns| turns as usize).or(game.max_turns); //A loaded game keeps its own limit unless given another
+    game.banner = cli.banner && stdout().is_terminal(); //Cursor jumps make no sense in a file
+    for board in game.boards_mut() {
+        if cli.notation == Notation::Letter {
+            board.column_labels = ColumnLabels::Letters;
+        }
+        board.style = cli.style;
+        board.palette = palette;
+        board.theme = cli.glyphs.theme();
+        if cli.recent {
+            board.recent = Some(VecDeque::new());
+        }
+    }
+    Some(game)
+}
 
 fn main() {
-    let mut player_board = Board::new(BoardVisibility::Visible);
-    let mut computer_board = Board::new(BoardVisibility::Hidden);
+    //A panic in raw mode would leave the shell without echo or line editing, so clean up before the message
+    let report = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        report(info);
+    }));
 
-    player_board.place_ship(2);
-    player_board.place_ship(3);
-    player_board.place_ship(4);
-    player_board.place_ship(5);
+    let cli = Cli::parse();
 
-    computer_board.place_ship(2);
-    computer_board.place_ship(3);
-    computer_board.place_ship(4);
-    computer_board.place_ship(5);
+    //Escape codes would only clutter a file or a pipe
+    let colored = !cli.no_color && std::env::var_os("NO_COLOR").is_none() && stdout().is_terminal();
+    let palette = colored.then(|| cli.colors.palette());
 
-    loop {
-        let mut stdout = stdout();
-        execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).unwrap();
-        stdout.flush().unwrap();
+    if let Some(path) = &cli.replay {
+        if let Err(e) = replay_game(path, Duration::from_millis(cli.speed), cli.lang, (palette, cli.glyphs.theme())) {
+            eprintln!("{}", (cli.lang.messages().load_error)(&e));
+            std::process::exit(1);
+        }
+        return;
+    }
 
-        println!("Your ships are placed: ");
-        println!("{}", player_board);
-        println!("The opponent's ships are: ");
-        println!("{}", computer_board);
+    let board_size = cli.board_size();
+    let mut seed = if cli.daily {
+        let days = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs() / 86_400); //UTC, so the day turns at the same moment everywhere
+        let seed = date_seed(days);
+        println!("{}", (cli.lang.messages().daily_seed)(seed));
+        seed
+    } else {
+        cli.seed.unwrap_or_else(|| rand::thread_rng().gen()) //Fresh game, but still replayable from the printed seed
+    };
 
-        let player = user_input();
-        let result = computer_board.fire(player);
+    let mut rules = Rules::default();
+    if cli.no_touch {
+        rules.adjacency = AdjacencyRule::NoTouch;
+    }
+    if cli.corners {
+        rules.adjacency = AdjacencyRule::Corners;
+    }
+    rules.diagonal = cli.diagonal;
+    rules.placement = cli.placement;
+    rules.mines = cli.mines;
+    rules.reveal_sunk = cli.reveal_sunk;
+    rules.fog = cli.fog;
 
-        match result {
-            CellState::Hit => println!("{}", "You hit a ship!".red()),
-            CellState::Miss => println!("{}", "You missed!".blue()),
-            _ => ()
+    let player_placement = if cli.manual || cli.drag { PlayerPlacement::Manual } else { PlayerPlacement::Random };
+    let difficulty = cli.difficulty;
+    let cursor_mode = cli.cursor;
+    let text = cli.lang.messages();
+
+    if cli.turn_time.is_some() && cursor_mode {
+        eprintln!("{}", text.turn_time_ignored);
+    }
+
+    let (host, address) = (cli.host, cli.connect.clone());
+    let mode = if host.is_some() || address.is_some() { GameMode::Network } else { cli.mode };
+    if mode == GameMode::Teams && (cli.manual || cli.drag || cli.load.is_some() || cli.record.is_some()) {
+        usage_error(text.team_game_limits);
+    }
+
+    let fleet = match &cli.fleet {
+        Some(value) => parse_fleet(value).unwrap_or_else(|e| usage_error((text.fleet_spec_error)(&e))),
+        None => CLASSIC_FLEET.to_vec(),
+    };
+    let side_fleet = |flag: &str, value: &Option<String>| match value { //Each is checked on its own, so the error names the flag
+        Some(value) => {
+            let side = parse_fleet(value).unwrap_or_else(|e| usage_error(format!("{}: {}", flag, (text.fleet_spec_error)(&e))));
+            check_fleet(&side, board_size.0, board_size.1).unwrap_or_else(|e| usage_error(format!("{}: {}", flag, (text.fleet_error)(&e))));
+            side
         }
+        None => fleet.clone(),
+    };
+    let fleets = (side_fleet("--player-fleet", &cli.player_fleet), side_fleet("--ai-fleet", &cli.ai_fleet));
 
-        println!("Enter to continue...");
-        io::stdin().read_line(&mut String::new()).expect("Failed");
+    if let Some(runs) = cli.placement_audit {
+        audit((runs, seed), board_size, rules, &fleet, text).unwrap_or_else(|e| usage_error((text.fleet_error)(&e)));
+        return;
+    }
 
-        if computer_board.game_over() {
-            println!("Congratulations! You sank all enemy ships");
-            break;
+    if let Some(games) = cli.bench {
+        if !matches!(mode, GameMode::Classic | GameMode::Salvo) {
+            usage_error(text.bench_modes);
         }
 
-        let opponent = opponent_move();
-        let result = player_board.fire(opponent);
+        let sides = [cli.versus.unwrap_or(difficulty), difficulty];
+        bench((games, seed), board_size, mode, rules, (&fleets.0, &fleets.1), sides, text).unwrap_or_else(|e| usage_error((text.fleet_error)(&e)));
+        return;
+    }
 
-        match result {
-            CellState::Hit => println!("{}", "Opponent has hit your ship!".red()),
-            CellState::Miss => println!("{}", "Opponent missed".blue()),
-            _ => ()
+    //Only games against the computer count, a second human would share the record
+    let profile_path = cli.profile.clone().or_else(default_profile_path).filter(|_| !matches!(mode, GameMode::Hotseat | GameMode::Network));
+    let mut profile = match profile_path.as_deref().map(read_profile) {
+        Some(Ok(profile)) => Some(profile),
+        Some(Err(e)) => { //Not overwritten with a fresh record either
+            eprintln!("{}: {}", text.profile_unreadable, e);
+            None
         }
+        None => None,
+    };
+
+    let Some(mut game) = new_game(&cli, cli.load.as_deref(), seed, (board_size, mode, rules, player_placement), (&fleets.0, &fleets.1), palette) else {
+        return; //The input ran out while placing ships
+    };
 
-        println!("Enter to continue...");
-        io::stdin().read_line(&mut String::new()).expect("Failed");
+    if game.mode == GameMode::Network {
+        let (mut connection, is_host) = connect(host, address, text).unwrap_or_else(|e| {
+            eprintln!("{}: {}", text.could_not_connect, e);
+            std::process::exit(1);
+        });
 
-        if player_board.game_over() {
-            println!("Opponent sank all your ships!");
-            break;
+        let winner = match network_game(&mut game, &mut connection, is_host, cursor_mode) {
+            Ok(winner) => winner,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        match winner {
+            Player::Human => println!("{}", text.you_won),
+            Player::Computer => println!("{}", text.you_lost),
         }
+
+        println!();
+        print!("{}", stats_side_by_side(&game, &game.player_stats, &game.computer_stats));
+        write_stats(&game, Some(winner), cli.stats_out.as_deref());
+        return;
+    }
+
+    let mut tally = (0, 0, 0); //Won, lost and drawn this session
+    loop {
+        let number = tally.0 + tally.1 + tally.2 + 1; //Of this game in the session
+        game.profile = profile;
+        let winner = loop {
+            if game.out_of_turns() { //Called off, the fleet with more of it afloat wins
+                println!("{}", (text.turn_limit_reached)(game.turns / 2));
+                match game.leader() {
+                    Some(player) => println!("{}", (text.ahead_at_limit)(&game.player_label(player))),
+                    None => println!("{}", text.draw_at_limit),
+                }
+                break game.leader();
+            }
+
+            let mined = game.player_label(game.turn);
+            if game.skip_mined_turn() {
+                println!("{}", (text.lost_turn_to_mine)(&mined));
+                pause(&game, text.press_enter); //Otherwise the next turn redraws over it
+                continue;
+            }
+
+            match game.turn {
+                Player::Human => {
+                    if game.mode == GameMode::Hotseat {
+                        pass_device(&game.player_label(Player::Human), text);
+                    }
+                    if game.mode == GameMode::Teams {
+                        redraw(&game, false);
+                        match choose_enemy_board(&game) {
+                            Some(board) => game.aim_at(Player::Computer, board),
+                            None => {
+                                println!("{}", text.you_left);
+                                return;
+                            }
+                        }
+                    }
+
+                    match human_turn(&mut game, cursor_mode) {
+                        TurnEnd::Next | TurnEnd::Again | TurnEnd::Chained => (), //Passed on with the turn, if it is over
+                        TurnEnd::Won => {
+                            if game.mode == GameMode::Hotseat {
+                                println!("{}", (text.player_wins)(&game.player_label(Player::Human)));
+                            } else {
+                                println!("{}", text.you_won);
+                            }
+                            break Some(Player::Human);
+                        }
+                        TurnEnd::Quit => {
+                            println!("{}", text.you_left);
+                            return;
+                        }
+                        TurnEnd::Surrendered => {
+                            surrender(&mut game, Player::Human);
+                            break Some(Player::Computer);
+                        }
+                    }
+                }
+                Player::Computer if game.mode == GameMode::Hotseat => { //The second human, playing from the other side
+                    game.swap_sides();
+                    pass_device(&game.player_label(Player::Computer), text);
+                    let end = human_turn(&mut game, cursor_mode);
+                    game.swap_sides();
+
+                    match end {
+                        TurnEnd::Next | TurnEnd::Again | TurnEnd::Chained => (),
+                        TurnEnd::Won => {
+                            println!("{}", (text.player_wins)(&game.player_label(Player::Computer)));
+                            break Some(Player::Computer);
+                        }
+                        TurnEnd::Quit => {
+                            println!("{}", text.you_left);
+                            return;
+                        }
+                        TurnEnd::Surrendered => {
+                            surrender(&mut game, Player::Computer);
+                            break Some(Player::Human);
+                        }
+                    }
+                }
+                Player::Computer => {
+                    println!("{}", turn_header(&game));
+                    let peeks = game.computer_peeks;
+                    let (fired, chained) = game.computer_reply();
+                    if game.mode == GameMode::Teams {
+                        println!("{}", (text.opponent_aims)(game.aimed.0 + 1));
+                    }
+                    if game.computer_peeks < peeks {
+                        println!("{}", text.opponent_peeked);
+                    }
+                    let palette = game.player_board.palette;
+
+                    for (_, result) in fired {
+                        match result {
+                            FireResult::Hit => println!("{}", tint(text.opponent_hit, palette, |palette| palette.hit)),
+                            FireResult::Miss => println!("{}", tint(text.opponent_missed, palette, |palette| palette.miss)),
+                            FireResult::Sunk(kind) => println!("{}", shout(&(text.opponent_sank)((text.ship)(kind)), palette)),
+                            FireResult::Mine => println!("{}", tint(text.opponent_hit_mine, palette, |palette| palette.warning)),
+                            FireResult::AlreadyFired => println!("{}", text.opponent_already_fired),
+                        }
+                    }
+                    show_banner(&game, true);
+                    pause(&game, text.press_enter);
+
+                    if game.all_sunk(Player::Human) {
+                        println!("{}", text.you_lost);
+                        break Some(Player::Computer);
+                    }
+                    if chained { //Keeps firing with its targeting until it misses
+                        println!("{}", text.opponent_fires_again);
+                    }
+                }
+            }
+        };
+
+        println!();
+        print!("{}", stats_side_by_side(&game, &game.player_stats, &game.computer_stats));
+        if matches!(player_placement, PlayerPlacement::Manual) && game.mode != GameMode::Hotseat { //The fleet we laid out ourselves was ours to defend
+            let (enemy_afloat, enemy_ships) = game.afloat(Player::Computer);
+            let (afloat, ships) = game.afloat(Player::Human);
+            println!("{}", (text.offense)(enemy_ships - enemy_afloat, enemy_ships, game.player_stats.shots));
+            println!("{}", (text.defense)(afloat, ships, game.computer_stats.shots));
+        }
+        write_stats(&game, winner, cli.stats_out.as_deref().map(|path| numbered_path(path, number)).as_deref());
+        if let (Some(profile), Some(path)) = (&mut profile, &profile_path) {
+            profile.add(winner, &game.player_stats);
+            if let Err(e) = save_profile(profile, path) {
+                println!("{}: {}", text.profile_failed, e);
+            }
+        }
+        if cli.final_boards {
+            println!();
+            print_final_boards(&game);
+        }
+
+        if let Some(path) = cli.record.as_deref().map(|path| numbered_path(path, number)) { //Keep the finished game for --replay
+            match save_game(&game, &path) {
+                Ok(()) => println!("{}", (text.recorded_to)(&path)),
+                Err(e) => println!("{}: {}", text.record_failed, e),
+            }
+        }
+
+        match winner { //Player one's wins and losses in a hotseat session
+            Some(Player::Human) => tally.0 += 1,
+            Some(Player::Computer) => tally.1 += 1,
+            None => tally.2 += 1,
+        }
+        println!();
+        println!("{}", (text.session_tally)(tally.0, tally.1, tally.2));
+        print!("{}", text.play_again);
+        stdout().flush().unwrap();
+        match read_stdin() {
+            Some(answer) if text.yes.contains(&answer.trim().to_lowercase().as_str()) => (),
+            _ => return,
+        }
+
+        seed = seed.wrapping_add(1); //Next layout, still replayable from the seed shown
+        let Some(next) = new_game(&cli, None, seed, (board_size, mode, rules, player_placement), (&fleets.0, &fleets.1), palette) else {
+            return;
+        };
+        game = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_in_raw_mode_restores_the_terminal() {
+        let unwound = std::panic::catch_unwind(|| {
+            let _raw = RawMode::enable(); //Off a terminal there is nothing to enable, and nothing may be left on either
+            panic!("mid turn");
+        });
+
+        assert!(unwound.is_err());
+        assert!(!terminal::is_raw_mode_enabled().unwrap());
     }
 }
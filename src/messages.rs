@@ -0,0 +1,690 @@
+use clap::ValueEnum;
+use std::time::Duration;
+use crate::{Axis, CompactError, FleetError, FleetSpecError, LoadError, OutOfBounds, ParseError, PlacementError, Profile, ShipKind, Stats, TemplateError, ThinkTime, MAX_BOARD_SIZE};
+
+#[derive(Copy, Clone, Default, PartialEq, ValueEnum)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Language::English => &ENGLISH,
+            Language::German => &GERMAN,
+        }
+    }
+}
+
+pub struct Messages { //Everything the game says to the player, in one language
+    pub ship: fn(ShipKind) -> &'static str,
+    pub you: &'static str,
+    pub opponent: &'static str,
+    pub player_one: &'static str,
+    pub player_two: &'static str,
+    pub shot_hit: &'static str, //Shot history entries
+    pub shot_miss: &'static str,
+    pub shot_mine: &'static str,
+    pub stats: fn(&Stats) -> String,
+    pub banner: fn(&str, &str, &str) -> String, //Shooter, result, cell
+    pub banner_hit: &'static str,
+    pub banner_miss: &'static str,
+    pub banner_mine: &'static str,
+
+    //Boards
+    pub seed: &'static str,
+    pub your_ships: &'static str,
+    pub their_ships: &'static str,
+    pub team_board: fn(&str, usize) -> String, //Title of one board of a team
+    pub which_board: fn(usize) -> String, //Boards on the enemy team
+    pub which_board_help: fn(usize) -> String,
+    pub board_already_sunk: fn(usize) -> String,
+    pub opponent_aims: fn(usize) -> String,
+    pub last_shots: &'static str,
+    pub turn_header: fn(Option<&str>) -> String, //Whose turn, None for the player at the keyboard
+    pub running_score: fn(i64, i64) -> String, //Ours and theirs
+    pub fleet: &'static str,
+    pub sunk: &'static str, //Marks sunk ships in the fleet list when colours are off
+    pub ships_remaining: fn(usize) -> String,
+    pub radar_tally: fn(usize, usize) -> String, //Hits, misses
+    pub too_small: fn(u16, u16, usize, usize) -> String, //Terminal columns and rows, then what the game needs
+
+    //Aiming
+    pub cursor_help: &'static str,
+    pub fire_prompt: &'static str,
+    pub shortcut_keys: fn(&str) -> String, //The keys and their commands
+    pub too_late_to_undo: &'static str,
+    pub nothing_to_undo: &'static str,
+    pub reveal_needs_debug: &'static str,
+    pub no_scans_left: &'static str,
+    pub nothing_to_scan: &'static str,
+    pub scan_usage: fn(usize, usize) -> String, //Last row and last column
+    pub scan_instead_of_salvo: &'static str,
+    pub scan_row: fn(usize) -> String,
+    pub scan_column: fn(&str) -> String,
+    pub scan_result: fn(&str, usize, usize) -> String, //The line, its hits and the scans left
+    pub peeked: fn(&str, usize) -> String, //The cell and the peeks left
+    pub no_peeks_left: &'static str,
+    pub nothing_to_peek: &'static str,
+    pub opponent_peeked: &'static str,
+    pub auto_needs_flag: &'static str,
+    pub auto_firing: fn(&str) -> String,
+    pub final_boards: &'static str,
+    pub session_tally: fn(usize, usize, usize) -> String, //Won, lost, drawn
+    pub play_again: &'static str,
+    pub lifetime: fn(&Profile) -> String,
+    pub profile_unreadable: &'static str,
+    pub profile_failed: &'static str,
+    pub offense: fn(usize, usize, usize) -> String, //Enemy ships sunk, of how many, with how many shots
+    pub defense: fn(usize, usize, usize) -> String, //Our ships afloat, of how many, against how many shots
+    pub hits_list: fn(usize, &str) -> String, //How many, and the cells
+    pub no_hits_yet: &'static str,
+    pub fire_again: &'static str,
+    pub opponent_fires_again: &'static str,
+    pub heatmap_needs_debug: &'static str,
+    pub heatmap_legend: &'static str,
+    pub no_network_save: &'static str,
+    pub no_team_save: &'static str,
+    pub team_game_limits: &'static str,
+    pub saved_to: fn(&str) -> String,
+    pub save_failed: &'static str,
+    pub already_fired_at: fn(&str) -> String,
+    pub fire_anyway: fn(&str) -> String,
+    pub yes: &'static [&'static str], //Answers that count as yes
+    pub parse_error: fn(&ParseError) -> String,
+    pub guide_empty: &'static str,
+    pub guide_separator: &'static str,
+    pub guide_numbers: &'static str,
+    pub guide_rows: fn(usize) -> String, //The last row
+    pub guide_columns: fn(usize) -> String,
+    pub guide_letters: fn(char) -> String,
+
+    //Placing ships
+    pub place_your_ships: &'static str,
+    pub your_fleet: &'static str,
+    pub keep_or_reshuffle: &'static str,
+    pub reshuffle: &'static [&'static str], //Answers that throw the layout away
+    pub placing: fn(&str, usize) -> String, //Ship name, size
+    pub start_prompt: &'static str,
+    pub orientation_prompt: &'static str,
+    pub orientation_prompt_diagonal: &'static str,
+    pub orientation_help: &'static str,
+    pub orientation_help_diagonal: &'static str,
+    pub placed_cells: fn(&str, &str) -> String, //Ship name, its cells
+    pub placement_error: fn(&PlacementError) -> String,
+    pub try_again: &'static str,
+    pub still_to_place: fn(&str) -> String, //The ships left, already listed
+    pub drag_help: &'static str,
+
+    //Turns
+    pub shot_of: fn(usize, usize) -> String,
+    pub time_up_turn: &'static str,
+    pub time_up_shot: &'static str,
+    pub you_hit: &'static str,
+    pub you_missed: &'static str,
+    pub miss_warm: &'static str,
+    pub miss_cold: &'static str,
+    pub you_sank: fn(&str) -> String,
+    pub you_hit_mine: &'static str,
+    pub you_already_fired: &'static str,
+    pub out_of_bounds: fn(&OutOfBounds) -> String,
+    pub salvo_tally: fn(usize, usize) -> String,
+    pub burst_tally: fn(usize, usize) -> String,
+    pub burst_usage: &'static str,
+    pub burst_duplicate: fn(&str) -> String,
+    pub burst_needs_computer: &'static str,
+    pub queued_shot: fn(&str, usize) -> String, //The cell and how many are still queued
+    pub queued_skipped: fn(&str) -> String,
+    pub continue_or_undo: &'static str,
+    pub took_back: fn(&str) -> String,
+    pub opponent_hit: &'static str,
+    pub opponent_missed: &'static str,
+    pub opponent_sank: fn(&str) -> String,
+    pub opponent_hit_mine: &'static str,
+    pub opponent_already_fired: &'static str,
+    pub lost_turn_to_mine: fn(&str) -> String,
+    pub press_enter: &'static str,
+    pub fast_on: &'static str,
+    pub fast_off: &'static str,
+    pub pass_device: fn(&str) -> String,
+
+    //Game over
+    pub you_won: &'static str,
+    pub you_lost: &'static str,
+    pub player_wins: fn(&str) -> String,
+    pub you_left: &'static str,
+    pub you_surrendered: &'static str,
+    pub player_surrendered: fn(&str) -> String,
+    pub turn_limit_reached: fn(usize) -> String,
+    pub ahead_at_limit: fn(&str) -> String,
+    pub draw_at_limit: &'static str,
+    pub recorded_to: fn(&str) -> String,
+    pub record_failed: &'static str,
+    pub stats_written: fn(&str) -> String,
+    pub stats_failed: &'static str,
+
+    //Benchmark
+    pub bench_games: fn(usize, u64) -> String, //Games, first seed
+    pub bench_columns: [&'static str; 4],
+    pub bench_unfinished: fn(usize) -> String,
+    pub bench_modes: &'static str,
+    pub input_closed: &'static str,
+    pub daily_seed: fn(u64) -> String,
+    pub audit_layouts: fn(usize, u64) -> String, //Layouts, first seed
+    pub audit_range: fn(usize, usize) -> String, //Fewest and most ships seen on a single cell
+
+    //Network
+    pub waiting_on_port: fn(u16) -> String,
+    pub connected: fn(&str) -> String,
+    pub could_not_connect: &'static str,
+    pub waiting_for_shot: &'static str,
+    pub disconnected: &'static str,
+    pub lost_connection: &'static str,
+    pub unexpected_message: fn(&str) -> String,
+    pub board_mismatch: fn(usize, usize) -> String,
+    pub no_hello: &'static str,
+    pub fleet_mismatch: fn(&str) -> String,
+    pub rules_mismatch: fn(&str) -> String,
+    pub no_answer: &'static str,
+    pub no_game_over_word: &'static str,
+    pub fired_off_board: fn(usize, usize) -> String,
+    pub did_not_fire: &'static str,
+
+    //Setting up
+    pub fleet_spec_error: fn(&FleetSpecError) -> String,
+    pub fleet_error: fn(&FleetError) -> String,
+    pub template_error: fn(&TemplateError) -> String,
+    pub compact_error: fn(&CompactError) -> String,
+    pub load_error: fn(&LoadError) -> String,
+    pub turn_time_ignored: &'static str,
+    pub board_dimensions: fn(usize, usize) -> String, //The smallest and largest rows or columns
+    pub host_or_connect: &'static str,
+}
+
+fn think(stats: &Stats, pick: fn(&ThinkTime) -> Duration) -> String { //Seconds for one timing of the stats, - if nothing was timed
+    stats.think_time().map_or("-".to_string(), |time| format!("{:.1}s", pick(&time).as_secs_f64()))
+}
+
+static ENGLISH: Messages = Messages {
+    ship: |kind| match kind {
+        ShipKind::Destroyer => "Destroyer",
+        ShipKind::Submarine => "Submarine",
+        ShipKind::Cruiser => "Cruiser",
+        ShipKind::Battleship => "Battleship",
+        ShipKind::Carrier => "Carrier",
+    },
+    you: "You",
+    opponent: "Opponent",
+    player_one: "Player 1",
+    player_two: "Player 2",
+    shot_hit: "Hit",
+    shot_miss: "Miss",
+    shot_mine: "Mine",
+    banner: |player, result, cell| format!("{}: {} at {}", player, result, cell),
+    banner_hit: "HIT",
+    banner_miss: "MISS",
+    banner_mine: "MINE",
+    stats: |stats| format!(
+        "Shots:    {}\nHits:     {}\nMisses:   {}\nAccuracy: {:.1}%\nTurns:    {}\nScore:    {}\nThinking: {}\nFastest:  {}\nSlowest:  {}",
+        stats.shots, stats.hits, stats.misses, stats.accuracy(), stats.turns, stats.score,
+        think(stats, |time| time.average), think(stats, |time| time.fastest), think(stats, |time| time.slowest)
+    ),
+
+    seed: "Game seed",
+    your_ships: "Your ships are placed",
+    their_ships: "The opponent's ships are",
+    team_board: |title, board| format!("{}, board {}", title, board),
+    which_board: |boards| format!("Fire at which enemy board (1-{})? ", boards),
+    which_board_help: |boards| format!("Type the number of an enemy board, from 1 to {}.", boards),
+    board_already_sunk: |board| format!("Every ship on enemy board {} is already sunk.", board),
+    opponent_aims: |board| format!("The opponent fires at your board {}.", board),
+    last_shots: "Last shots",
+    turn_header: |side| match side {
+        Some(side) => format!(">>> {}'S TURN <<<", side.to_uppercase()),
+        None => ">>> YOUR TURN <<<".to_string(),
+    },
+    running_score: |ours, theirs| format!("Score: {} to {}", ours, theirs),
+    fleet: "Fleet:",
+    sunk: "sunk",
+    ships_remaining: |cells| format!("Ships remaining: {} cells", cells),
+    radar_tally: |hits, misses| format!("Hits: {}  Misses: {}", hits, misses),
+    too_small: |columns, rows, width, height| format!("The terminal is {}x{} but the game needs {}x{}, enlarge the window to see everything.", columns, rows, width, height),
+
+    cursor_help: "Arrow keys to aim, Enter to fire, Esc to quit",
+    shortcut_keys: |keys| format!("Keys: {}. Type one alone and press Enter for its command.", keys),
+    fire_prompt: "Enter the coordinates to fire to (row, column or e.g. B7), save, history, radar, surrender or quit: ",
+    too_late_to_undo: "The opponent has already moved, it is too late to undo.",
+    nothing_to_undo: "There is no shot to undo.",
+    reveal_needs_debug: "reveal is only available with --debug.",
+    no_scans_left: "You have no scans left.",
+    nothing_to_scan: "Every cell of that line has been fired at already.",
+    scan_usage: |row, column| format!("Scan with e.g. scan row 3 or scan column 4, rows go up to {} and columns up to {}.", row, column),
+    scan_instead_of_salvo: "A scan takes the whole turn, so it has to come before the first shot of a salvo.",
+    scan_row: |row| format!("row {}", row),
+    scan_column: |column| format!("column {}", column),
+    scan_result: |line, hits, left| format!("The scan of {} hit {} time(s). Scans left: {}", line, hits, left),
+    peeked: |cell, left| format!("There is a ship at {}. Peeks left: {}", cell, left),
+    no_peeks_left: "You have no peeks left.",
+    nothing_to_peek: "Every ship cell left has been uncovered already.",
+    opponent_peeked: "Your opponent peeked at one of your ships.",
+    auto_needs_flag: "auto is only available with --auto-fire.",
+    auto_firing: |cell| format!("Firing at the best guess, {}.", cell),
+    final_boards: "The whole game, with every ship, hit and miss:",
+    session_tally: |won, lost, drawn| format!("This session: {} won, {} lost, {} drawn.", won, lost, drawn),
+    play_again: "Play again? (y/n) ",
+    lifetime: |profile| format!("Lifetime: {} played, {} won, {} lost, {:.1}% accuracy", profile.played, profile.won, profile.lost, profile.accuracy()),
+    profile_unreadable: "Could not read the lifetime record, it is left as it is",
+    profile_failed: "Could not save the lifetime record",
+    offense: |sunk, ships, shots| format!("Offense: you sank {} of {} enemy ships with {} shots.", sunk, ships, shots),
+    defense: |afloat, ships, shots| format!("Defense: {} of your {} ships stayed afloat against {} shots.", afloat, ships, shots),
+    hits_list: |count, cells| format!("{} hit(s): {}", count, cells),
+    no_hits_yet: "No hits yet.",
+    fire_again: "A hit, fire again!",
+    opponent_fires_again: "Your opponent hit and fires again.",
+    heatmap_needs_debug: "heatmap is only available with --debug.",
+    heatmap_legend: "Brighter cells are covered by more of the placements the remaining ships could still have.",
+    no_network_save: "Network games cannot be saved.",
+    no_team_save: "Team games cannot be saved.",
+    team_game_limits: "team games lay out every fleet at random and cannot be saved",
+    saved_to: |path| format!("Game saved to {}", path),
+    save_failed: "Could not save the game",
+    already_fired_at: |cell| format!("You already fired at {}, pick another cell.", cell),
+    fire_anyway: |cell| format!("You've already fired at {}, fire anyway? (y/n) ", cell),
+    yes: &["y", "yes"],
+    parse_error: |e| match e {
+        ParseError::Empty => "No coordinates were entered.".to_string(),
+        ParseError::WrongFieldCount(count) => format!("Expected a row and a column, but got {} field(s).", count),
+        ParseError::NotANumber(token) => format!("'{}' is not a number.", token),
+        ParseError::OutOfRange { axis: Axis::Row, value } => format!("There is no row {} on this board.", value),
+        ParseError::OutOfRange { axis: Axis::Column, value } => format!("There is no column {} on this board.", value),
+        ParseError::LetterOutOfRange(letter) => format!("There is no column {} on this board.", letter),
+    },
+    guide_empty: "Type a row and a column, e.g. 3, 4 or D3.",
+    guide_separator: "Separate the row and column with a single comma, e.g. 3, 4.",
+    guide_numbers: "Rows and columns are whole numbers, e.g. 3, 4.",
+    guide_rows: |last| format!("Rows go from 0 to {}.", last),
+    guide_columns: |last| format!("Columns go from 0 to {}.", last),
+    guide_letters: |last| format!("Column letters go from A to {}.", last),
+
+    place_your_ships: "Place your ships: ",
+    your_fleet: "Your fleet:",
+    keep_or_reshuffle: "Press Enter to keep this fleet, or type r to reshuffle it: ",
+    reshuffle: &["r", "reshuffle"],
+    placing: |ship, size| format!("Placing your {} ({} cells)", ship, size),
+    start_prompt: "Enter the starting cell (row, column or e.g. B7): ",
+    orientation_prompt: "Enter the orientation (h for horizontal, v for vertical, -h or -v to run left or up from the cell): ",
+    orientation_prompt_diagonal: "Enter the orientation (h for horizontal, v for vertical, d for diagonal down, u for diagonal up, with a - in front to run back from the cell): ",
+    orientation_help: "Please enter h or v, or -h or -v.",
+    orientation_help_diagonal: "Please enter h, v, d or u, with or without a - in front.",
+    placed_cells: |ship, cells| format!("Your {} is on {}", ship, cells),
+    placement_error: |e| match e {
+        PlacementError::OffBoard => "The ship would run off the board.",
+        PlacementError::Overlap => "The ship would overlap another ship.",
+        PlacementError::TooClose => "Ships are not allowed to touch each other.",
+        PlacementError::NoRoom => "No room was found for the ship.",
+    }.to_string(),
+    try_again: "Enter to try again...",
+    still_to_place: |ships| format!("Still to place: {}", ships),
+    drag_help: "Drag from the first to the last cell of a ship, Esc to type coordinates instead",
+
+    shot_of: |shot, shots| format!("Shot {} of {}", shot, shots),
+    time_up_turn: "Time is up, the rest of this turn is forfeited.",
+    time_up_shot: "Time is up, your shot is forfeited.",
+    you_hit: "You hit a ship!",
+    you_missed: "You missed!",
+    miss_warm: "Warm, a ship is very close.",
+    miss_cold: "Cold, no ship anywhere near.",
+    you_sank: |ship| format!("You sank the enemy {}!", ship),
+    you_hit_mine: "Boom! That was a mine, you lose your next turn.",
+    you_already_fired: "You already fired there.",
+    out_of_bounds: |OutOfBounds(position)| format!("{},{} is not on the board.", position.row, position.column),
+    salvo_tally: |hits, misses| format!("Salvo: {} hit(s), {} miss(es)", hits, misses),
+    burst_tally: |hits, misses| format!("Burst: {} hit(s), {} miss(es)", hits, misses),
+    burst_usage: "Queue shots with e.g. burst A1 B2 C3, one is fired each turn.",
+    burst_duplicate: |cell| format!("{} is in the burst twice, it is only fired at once.", cell),
+    burst_needs_computer: "burst is only available against the computer.",
+    queued_shot: |cell, left| format!("Firing the queued shot at {}, {} more queued.", cell, left),
+    queued_skipped: |cell| format!("Skipping the queued shot at {}, it has been fired at already.", cell),
+    continue_or_undo: "Enter to continue, or type undo to take this turn back...",
+    took_back: |cell| format!("Took back your shot at {}", cell),
+    opponent_hit: "Opponent has hit your ship!",
+    opponent_missed: "Opponent missed",
+    opponent_sank: |ship| format!("Opponent sank your {}!", ship),
+    opponent_hit_mine: "Opponent hit a mine and loses its next turn!",
+    opponent_already_fired: "Opponent fired at a cell it already fired at",
+    lost_turn_to_mine: |player| format!("{} lost this turn to a mine.", player),
+    press_enter: "Enter to continue...",
+    fast_on: "Fast play: turns go on without waiting for Enter.",
+    fast_off: "Turns wait for Enter again.",
+    pass_device: |player| format!("Pass the device to {}. Press Enter when ready...", player),
+
+    you_won: "Congratulations! You sank all enemy ships",
+    you_lost: "Opponent sank all your ships!",
+    turn_limit_reached: |turns| format!("Both sides have had their {} turns.", turns),
+    ahead_at_limit: |player| format!("Winner on ship cells still afloat: {}!", player),
+    draw_at_limit: "Both fleets have the same number of ship cells afloat, it is a draw.",
+    player_wins: |player| format!("{} sank all enemy ships and wins!", player),
+    you_left: "You abandoned the game.",
+    you_surrendered: "You surrendered, the game is lost. Above is where the enemy fleet was.",
+    player_surrendered: |player| format!("{} surrendered and lost the game.", player),
+    recorded_to: |path| format!("Game recorded to {}", path),
+    record_failed: "Could not record the game",
+    stats_written: |path| format!("Statistics written to {}", path),
+    stats_failed: "Could not write the statistics",
+
+    bench_games: |games, seed| format!("{} game(s) from seed {}, the sides take turns firing first", games, seed),
+    bench_columns: ["Side", "Wins", "Win rate", "Avg turns to win"],
+    bench_unfinished: |games| format!("Unfinished: {}", games),
+    bench_modes: "--bench only plays classic or salvo games",
+    input_closed: "No more input, ending the game.",
+    daily_seed: |seed| format!("Daily challenge, seed {}", seed),
+    audit_layouts: |layouts, seed| format!("Ship cells over {} layout(s) from seed {}", layouts, seed),
+    audit_range: |fewest, most| format!("Fewest on a cell: {}, most: {}", fewest, most),
+
+    waiting_on_port: |port| format!("Waiting for an opponent on port {}...", port),
+    connected: |peer| format!("{} connected", peer),
+    could_not_connect: "Could not connect",
+    waiting_for_shot: "Waiting for the opponent to fire...",
+    disconnected: "The opponent disconnected.",
+    lost_connection: "Lost the connection",
+    unexpected_message: |line| format!("The opponent sent something unexpected: {}", line),
+    board_mismatch: |rows, columns| format!("The opponent is playing on a {}x{} board.", rows, columns),
+    no_hello: "The opponent did not say hello.",
+    fleet_mismatch: |fleet| format!("The opponent is playing with another fleet: {}.", fleet),
+    rules_mismatch: |rules| format!("The opponent is playing with other rules: {}.", rules),
+    no_answer: "The opponent did not answer the shot.",
+    no_game_over_word: "The opponent did not say whether the game is over.",
+    fired_off_board: |row, column| format!("The opponent fired off the board at {},{}.", row, column),
+    did_not_fire: "The opponent did not fire.",
+
+    fleet_spec_error: |e| match e {
+        FleetSpecError::NoShipOfSize(size) => format!("There is no ship of size {}", size),
+        FleetSpecError::UnknownShip(name) => format!("Unknown ship '{}'", name),
+    },
+    fleet_error: |e| match e {
+        FleetError::Empty => "The fleet has no ships.".to_string(),
+        FleetError::ShipTooLong(kind) => format!("The {} does not fit on a board this small.", (ENGLISH.ship)(*kind)),
+        FleetError::TooManyCells { needed, available } => format!("The fleet needs {} cells but the board only has {}.", needed, available),
+        FleetError::TooCrowded { needed, limit } => format!(
+            "The fleet needs about {} cells, more than the {} that can be filled at random. Try a larger board or a smaller fleet.", needed, limit
+        ),
+        FleetError::NoRoomToSpace { needed, available } => format!(
+            "Without touching, the fleet needs {} cells counting the water around each ship, but the board only has room for {}. Allow touching ships or try a larger board.", needed, available
+        ),
+        FleetError::NoLayout => "Couldn't find room for the whole fleet. Try a larger board or a smaller fleet.".to_string(),
+    },
+    template_error: |e| match e {
+        TemplateError::Empty => "The template has no rows.".to_string(),
+        TemplateError::RaggedRow { row, length } => format!("Row {} of the template has {} cells, unlike the first row.", row, length),
+        TemplateError::UnknownCharacter { position, character } => format!(
+            "The template has '{}' at {},{}, only '#' for ships and '.' for water are allowed.", character, position.row, position.column
+        ),
+        TemplateError::Bent(position) => format!("The ship at {},{} in the template is not a straight line.", position.row, position.column),
+        TemplateError::NoShipOfSize(size) => format!("The template has a ship of size {}, but there is no such ship.", size),
+    },
+    compact_error: |e| match e {
+        CompactError::MissingPart => "A compact board needs a size, cells and ships separated by '/'.".to_string(),
+        CompactError::BadSize(size) => format!("'{}' is not a board size like 10x10.", size),
+        CompactError::NoCells => "A board needs at least one row and one column.".to_string(),
+        CompactError::TooLarge { rows, columns } => format!("A {}x{} board is larger than the {} rows and columns a board can have.", rows, columns, MAX_BOARD_SIZE),
+        CompactError::UnknownCharacter(character) => format!("'{}' is not a cell of a compact board.", character),
+        CompactError::WrongCellCount { expected, found } => format!("The board has {} cells, but {} were given.", expected, found),
+        CompactError::BadShip(ship) => format!("'{}' is not a ship on this board.", ship),
+    },
+    load_error: |e| match e {
+        LoadError::Io(e) => format!("Could not read the save file: {}", e),
+        LoadError::Format(e) => format!("The save file is not a valid saved game: {}", e),
+        LoadError::GridSize => "The saved grid does not match the board size.".to_string(),
+        LoadError::ShipOffBoard(kind) => format!("The saved {} lies outside the board.", (ENGLISH.ship)(*kind)),
+        LoadError::ShipMismatch(kind) => format!("The saved {} does not match the grid.", (ENGLISH.ship)(*kind)),
+        LoadError::ShipOverlap(kind) => format!("The saved {} overlaps another ship.", (ENGLISH.ship)(*kind)),
+        LoadError::StrayShipCell(position) => format!("The saved grid shows a ship at {},{} that no ship owns.", position.row, position.column),
+        LoadError::ShotOffBoard(position) => format!("The saved history has a shot at {},{}, outside the board.", position.row, position.column),
+    },
+    turn_time_ignored: "--turn-time only applies when typing coordinates, ignoring it with --cursor",
+    board_dimensions: |min, max| format!("board dimensions must be numbers from {} to {}", min, max),
+    host_or_connect: "expected --host <port> or --connect <addr>",
+};
+
+static GERMAN: Messages = Messages {
+    ship: |kind| match kind {
+        ShipKind::Destroyer => "Zerstörer",
+        ShipKind::Submarine => "U-Boot",
+        ShipKind::Cruiser => "Kreuzer",
+        ShipKind::Battleship => "Schlachtschiff",
+        ShipKind::Carrier => "Flugzeugträger",
+    },
+    you: "Du",
+    opponent: "Gegner",
+    player_one: "Spieler 1",
+    player_two: "Spieler 2",
+    shot_hit: "Treffer",
+    shot_miss: "Wasser",
+    shot_mine: "Mine",
+    banner: |player, result, cell| format!("{}: {} auf {}", player, result, cell),
+    banner_hit: "TREFFER",
+    banner_miss: "WASSER",
+    banner_mine: "MINE",
+    stats: |stats| format!(
+        "Schüsse:  {}\nTreffer:  {}\nDaneben:  {}\nQuote:    {:.1}%\nZüge:     {}\nPunkte:   {}\nBedenken: {}\nSchnell:  {}\nLangsam:  {}",
+        stats.shots, stats.hits, stats.misses, stats.accuracy(), stats.turns, stats.score,
+        think(stats, |time| time.average), think(stats, |time| time.fastest), think(stats, |time| time.slowest)
+    ),
+
+    seed: "Spiel-Seed",
+    your_ships: "Deine Schiffe",
+    their_ships: "Die Schiffe des Gegners",
+    team_board: |title, board| format!("{}, Spielfeld {}", title, board),
+    which_board: |boards| format!("Auf welches gegnerische Spielfeld feuern (1-{})? ", boards),
+    which_board_help: |boards| format!("Gib die Nummer eines gegnerischen Spielfelds ein, von 1 bis {}.", boards),
+    board_already_sunk: |board| format!("Alle Schiffe auf dem gegnerischen Spielfeld {} sind schon versenkt.", board),
+    opponent_aims: |board| format!("Der Gegner feuert auf dein Spielfeld {}.", board),
+    last_shots: "Letzte Schüsse",
+    turn_header: |side| match side {
+        Some(side) => format!(">>> {} IST DRAN <<<", side.to_uppercase()),
+        None => ">>> DU BIST DRAN <<<".to_string(),
+    },
+    running_score: |ours, theirs| format!("Punkte: {} zu {}", ours, theirs),
+    fleet: "Flotte:",
+    sunk: "versenkt",
+    ships_remaining: |cells| format!("Verbleibende Schiffsfelder: {}", cells),
+    radar_tally: |hits, misses| format!("Treffer: {}  Fehlschüsse: {}", hits, misses),
+    too_small: |columns, rows, width, height| format!("Das Terminal ist {}x{} groß, das Spiel braucht {}x{}. Vergrößere das Fenster, um alles zu sehen.", columns, rows, width, height),
+
+    cursor_help: "Pfeiltasten zum Zielen, Enter zum Feuern, Esc zum Beenden",
+    shortcut_keys: |keys| format!("Tasten: {}. Allein eingegeben und mit Enter bestätigt stehen sie für ihren Befehl.", keys),
+    fire_prompt: "Koordinaten für den Schuss (Zeile, Spalte oder z.B. B7), save, history, radar, surrender oder quit: ",
+    too_late_to_undo: "Der Gegner hat schon gezogen, für undo ist es zu spät.",
+    nothing_to_undo: "Es gibt keinen Schuss zum Zurücknehmen.",
+    reveal_needs_debug: "reveal gibt es nur mit --debug.",
+    no_scans_left: "Du hast keine Scans mehr.",
+    nothing_to_scan: "Auf jedes Feld dieser Linie wurde schon geschossen.",
+    scan_usage: |row, column| format!("Scanne z.B. mit scan row 3 oder scan column 4, die Zeilen gehen bis {} und die Spalten bis {}.", row, column),
+    scan_instead_of_salvo: "Ein Scan braucht den ganzen Zug und muss daher vor dem ersten Schuss einer Salve kommen.",
+    scan_row: |row| format!("Zeile {}", row),
+    scan_column: |column| format!("Spalte {}", column),
+    scan_result: |line, hits, left| format!("Der Scan von {} hat {}-mal getroffen. Übrige Scans: {}", line, hits, left),
+    peeked: |cell, left| format!("Auf {} liegt ein Schiff. Übrige Blicke: {}", cell, left),
+    no_peeks_left: "Du hast keine Blicke mehr.",
+    nothing_to_peek: "Jedes übrige Schiffsfeld ist schon aufgedeckt.",
+    opponent_peeked: "Dein Gegner hat eines deiner Schiffe erspäht.",
+    auto_needs_flag: "auto gibt es nur mit --auto-fire.",
+    auto_firing: |cell| format!("Schuss auf die beste Vermutung, {}.", cell),
+    final_boards: "Das ganze Spiel, mit jedem Schiff, Treffer und Fehlschuss:",
+    session_tally: |won, lost, drawn| format!("Diese Sitzung: {} gewonnen, {} verloren, {} unentschieden.", won, lost, drawn),
+    play_again: "Noch eine Runde? (j/n) ",
+    lifetime: |profile| format!("Bilanz: {} gespielt, {} gewonnen, {} verloren, {:.1}% Trefferquote", profile.played, profile.won, profile.lost, profile.accuracy()),
+    profile_unreadable: "Die Bilanz konnte nicht gelesen werden, sie bleibt unverändert",
+    profile_failed: "Die Bilanz konnte nicht gespeichert werden",
+    offense: |sunk, ships, shots| format!("Angriff: du hast {} von {} gegnerischen Schiffen mit {} Schüssen versenkt.", sunk, ships, shots),
+    defense: |afloat, ships, shots| format!("Verteidigung: {} deiner {} Schiffe blieben gegen {} Schüsse über Wasser.", afloat, ships, shots),
+    hits_list: |count, cells| format!("{} Treffer: {}", count, cells),
+    no_hits_yet: "Noch keine Treffer.",
+    fire_again: "Ein Treffer, schieß noch einmal!",
+    opponent_fires_again: "Dein Gegner hat getroffen und schießt noch einmal.",
+    heatmap_needs_debug: "heatmap gibt es nur mit --debug.",
+    heatmap_legend: "Je heller ein Feld, desto mehr mögliche Lagen der übrigen Schiffe decken es ab.",
+    no_network_save: "Netzwerkspiele können nicht gespeichert werden.",
+    no_team_save: "Teamspiele können nicht gespeichert werden.",
+    team_game_limits: "Teamspiele stellen jede Flotte zufällig auf und können nicht gespeichert werden",
+    saved_to: |path| format!("Spiel gespeichert in {}", path),
+    save_failed: "Das Spiel konnte nicht gespeichert werden",
+    already_fired_at: |cell| format!("Auf {} hast du schon geschossen, wähle ein anderes Feld.", cell),
+    fire_anyway: |cell| format!("Auf {} hast du schon geschossen, trotzdem feuern? (j/n) ", cell),
+    yes: &["j", "ja", "y", "yes"],
+    parse_error: |e| match e {
+        ParseError::Empty => "Es wurden keine Koordinaten eingegeben.".to_string(),
+        ParseError::WrongFieldCount(count) => format!("Erwartet werden eine Zeile und eine Spalte, eingegeben wurden {} Feld(er).", count),
+        ParseError::NotANumber(token) => format!("'{}' ist keine Zahl.", token),
+        ParseError::OutOfRange { axis: Axis::Row, value } => format!("Eine Zeile {} gibt es auf diesem Brett nicht.", value),
+        ParseError::OutOfRange { axis: Axis::Column, value } => format!("Eine Spalte {} gibt es auf diesem Brett nicht.", value),
+        ParseError::LetterOutOfRange(letter) => format!("Eine Spalte {} gibt es auf diesem Brett nicht.", letter),
+    },
+    guide_empty: "Gib eine Zeile und eine Spalte ein, z.B. 3, 4 oder D3.",
+    guide_separator: "Trenne Zeile und Spalte mit genau einem Komma, z.B. 3, 4.",
+    guide_numbers: "Zeilen und Spalten sind ganze Zahlen, z.B. 3, 4.",
+    guide_rows: |last| format!("Die Zeilen gehen von 0 bis {}.", last),
+    guide_columns: |last| format!("Die Spalten gehen von 0 bis {}.", last),
+    guide_letters: |last| format!("Die Spaltenbuchstaben gehen von A bis {}.", last),
+
+    place_your_ships: "Setze deine Schiffe: ",
+    your_fleet: "Deine Flotte:",
+    keep_or_reshuffle: "Enter behält diese Flotte, n mischt sie neu: ",
+    reshuffle: &["n", "neu", "r", "reshuffle"],
+    placing: |ship, size| format!("Setze dein Schiff {} ({} Felder)", ship, size),
+    start_prompt: "Startfeld (Zeile, Spalte oder z.B. B7): ",
+    orientation_prompt: "Ausrichtung (h für horizontal, v für vertikal, -h oder -v für nach links oder oben ab dem Feld): ",
+    orientation_prompt_diagonal: "Ausrichtung (h für horizontal, v für vertikal, d für diagonal abwärts, u für diagonal aufwärts, mit einem - davor rückwärts ab dem Feld): ",
+    orientation_help: "Bitte h oder v eingeben, oder -h oder -v.",
+    orientation_help_diagonal: "Bitte h, v, d oder u eingeben, mit oder ohne - davor.",
+    placed_cells: |ship, cells| format!("Dein Schiff {} liegt auf {}", ship, cells),
+    placement_error: |e| match e {
+        PlacementError::OffBoard => "Das Schiff würde über den Rand ragen.",
+        PlacementError::Overlap => "Das Schiff würde ein anderes Schiff überlappen.",
+        PlacementError::TooClose => "Schiffe dürfen sich nicht berühren.",
+        PlacementError::NoRoom => "Für das Schiff wurde kein Platz gefunden.",
+    }.to_string(),
+    try_again: "Enter für einen neuen Versuch...",
+    still_to_place: |ships| format!("Noch zu setzen: {}", ships),
+    drag_help: "Ziehe vom ersten bis zum letzten Feld eines Schiffs, Esc, um Koordinaten einzutippen",
+
+    shot_of: |shot, shots| format!("Schuss {} von {}", shot, shots),
+    time_up_turn: "Die Zeit ist um, der Rest dieses Zuges verfällt.",
+    time_up_shot: "Die Zeit ist um, dein Schuss verfällt.",
+    you_hit: "Treffer!",
+    you_missed: "Daneben!",
+    miss_warm: "Warm, ein Schiff ist ganz nah.",
+    miss_cold: "Kalt, weit und breit kein Schiff.",
+    you_sank: |ship| format!("Du hast den gegnerischen {} versenkt!", ship),
+    you_hit_mine: "Bumm! Das war eine Mine, du setzt den nächsten Zug aus.",
+    you_already_fired: "Dort hast du schon hingeschossen.",
+    out_of_bounds: |OutOfBounds(position)| format!("{},{} liegt nicht auf dem Brett.", position.row, position.column),
+    salvo_tally: |hits, misses| format!("Salve: {} Treffer, {} daneben", hits, misses),
+    burst_tally: |hits, misses| format!("Serie: {} Treffer, {} daneben", hits, misses),
+    burst_usage: "Stelle Schüsse mit z.B. burst A1 B2 C3 an, jeden Zug wird einer abgefeuert.",
+    burst_duplicate: |cell| format!("{} steht zweimal in der Serie, darauf wird nur einmal geschossen.", cell),
+    burst_needs_computer: "burst gibt es nur gegen den Computer.",
+    queued_shot: |cell, left| format!("Vorgemerkter Schuss auf {}, noch {} vorgemerkt.", cell, left),
+    queued_skipped: |cell| format!("Vorgemerkter Schuss auf {} entfällt, dorthin wurde schon geschossen.", cell),
+    continue_or_undo: "Enter zum Fortfahren, oder undo, um den Zug zurückzunehmen...",
+    took_back: |cell| format!("Schuss auf {} zurückgenommen", cell),
+    opponent_hit: "Der Gegner hat dein Schiff getroffen!",
+    opponent_missed: "Der Gegner hat danebengeschossen",
+    opponent_sank: |ship| format!("Der Gegner hat deinen {} versenkt!", ship),
+    opponent_hit_mine: "Der Gegner ist auf eine Mine gestoßen und setzt den nächsten Zug aus!",
+    opponent_already_fired: "Der Gegner hat auf ein Feld geschossen, das er schon beschossen hatte",
+    lost_turn_to_mine: |player| format!("{}: dieser Zug fällt wegen einer Mine aus.", player),
+    press_enter: "Enter zum Fortfahren...",
+    fast_on: "Schnelles Spiel: die Züge gehen ohne Enter weiter.",
+    fast_off: "Die Züge warten wieder auf Enter.",
+    pass_device: |player| format!("Gib das Gerät an {} weiter. Enter, wenn bereit...", player),
+
+    you_won: "Glückwunsch! Du hast alle gegnerischen Schiffe versenkt",
+    you_lost: "Der Gegner hat alle deine Schiffe versenkt!",
+    turn_limit_reached: |turns| format!("Beide Seiten hatten ihre {} Züge.", turns),
+    ahead_at_limit: |player| format!("Sieger nach Schiffsfeldern über Wasser: {}!", player),
+    draw_at_limit: "Beide Flotten haben gleich viele Schiffsfelder über Wasser, unentschieden.",
+    player_wins: |player| format!("{} hat alle gegnerischen Schiffe versenkt und gewinnt!", player),
+    you_left: "Du hast das Spiel aufgegeben.",
+    you_surrendered: "Du hast kapituliert, das Spiel ist verloren. Oben siehst du, wo die gegnerische Flotte lag.",
+    player_surrendered: |player| format!("{} hat kapituliert und das Spiel verloren.", player),
+    recorded_to: |path| format!("Spiel aufgezeichnet in {}", path),
+    record_failed: "Das Spiel konnte nicht aufgezeichnet werden",
+    stats_written: |path| format!("Statistik geschrieben nach {}", path),
+    stats_failed: "Die Statistik konnte nicht geschrieben werden",
+
+    bench_games: |games, seed| format!("{} Spiel(e) ab Seed {}, die Seiten beginnen abwechselnd", games, seed),
+    bench_columns: ["Seite", "Siege", "Quote", "Züge bis Sieg"],
+    bench_unfinished: |games| format!("Nicht beendet: {}", games),
+    bench_modes: "--bench spielt nur klassische oder Salven-Spiele",
+    input_closed: "Keine Eingabe mehr, das Spiel endet.",
+    daily_seed: |seed| format!("Tägliche Herausforderung, Seed {}", seed),
+    audit_layouts: |layouts, seed| format!("Schiffsfelder über {} Aufstellung(en) ab Seed {}", layouts, seed),
+    audit_range: |fewest, most| format!("Wenigste auf einem Feld: {}, meiste: {}", fewest, most),
+
+    waiting_on_port: |port| format!("Warte auf einen Gegner auf Port {}...", port),
+    connected: |peer| format!("{} ist verbunden", peer),
+    could_not_connect: "Keine Verbindung möglich",
+    waiting_for_shot: "Warte auf den Schuss des Gegners...",
+    disconnected: "Der Gegner hat die Verbindung getrennt.",
+    lost_connection: "Die Verbindung ist abgebrochen",
+    unexpected_message: |line| format!("Der Gegner hat etwas Unerwartetes gesendet: {}", line),
+    board_mismatch: |rows, columns| format!("Der Gegner spielt auf einem {}x{} Brett.", rows, columns),
+    no_hello: "Der Gegner hat nicht gegrüßt.",
+    fleet_mismatch: |fleet| format!("Der Gegner spielt mit einer anderen Flotte: {}.", fleet),
+    rules_mismatch: |rules| format!("Der Gegner spielt mit anderen Regeln: {}.", rules),
+    no_answer: "Der Gegner hat den Schuss nicht beantwortet.",
+    no_game_over_word: "Der Gegner hat nicht gesagt, ob das Spiel vorbei ist.",
+    fired_off_board: |row, column| format!("Der Gegner hat außerhalb des Bretts auf {},{} geschossen.", row, column),
+    did_not_fire: "Der Gegner hat nicht geschossen.",
+
+    fleet_spec_error: |e| match e {
+        FleetSpecError::NoShipOfSize(size) => format!("Es gibt kein Schiff der Größe {}", size),
+        FleetSpecError::UnknownShip(name) => format!("Unbekanntes Schiff '{}'", name),
+    },
+    fleet_error: |e| match e {
+        FleetError::Empty => "Die Flotte hat keine Schiffe.".to_string(),
+        FleetError::ShipTooLong(kind) => format!("Das Schiff {} passt nicht auf ein so kleines Brett.", (GERMAN.ship)(*kind)),
+        FleetError::TooManyCells { needed, available } => format!("Die Flotte braucht {} Felder, das Brett hat aber nur {}.", needed, available),
+        FleetError::TooCrowded { needed, limit } => format!(
+            "Die Flotte braucht etwa {} Felder, mehr als die {}, die sich zufällig füllen lassen. Versuche ein größeres Brett oder eine kleinere Flotte.", needed, limit
+        ),
+        FleetError::NoRoomToSpace { needed, available } => format!(
+            "Ohne Berührung braucht die Flotte {} Felder samt dem Wasser um jedes Schiff, das Brett hat aber nur Platz für {}. Erlaube sich berührende Schiffe oder versuche ein größeres Brett.", needed, available
+        ),
+        FleetError::NoLayout => "Es wurde kein Platz für die ganze Flotte gefunden. Versuche ein größeres Brett oder eine kleinere Flotte.".to_string(),
+    },
+    template_error: |e| match e {
+        TemplateError::Empty => "Die Vorlage hat keine Zeilen.".to_string(),
+        TemplateError::RaggedRow { row, length } => format!("Zeile {} der Vorlage hat {} Felder, anders als die erste Zeile.", row, length),
+        TemplateError::UnknownCharacter { position, character } => format!(
+            "Die Vorlage hat '{}' auf {},{}, erlaubt sind nur '#' für Schiffe und '.' für Wasser.", character, position.row, position.column
+        ),
+        TemplateError::Bent(position) => format!("Das Schiff auf {},{} in der Vorlage ist keine gerade Linie.", position.row, position.column),
+        TemplateError::NoShipOfSize(size) => format!("Die Vorlage hat ein Schiff der Größe {}, so ein Schiff gibt es aber nicht.", size),
+    },
+    compact_error: |e| match e {
+        CompactError::MissingPart => "Ein kompaktes Brett braucht Größe, Felder und Schiffe, getrennt durch '/'.".to_string(),
+        CompactError::BadSize(size) => format!("'{}' ist keine Brettgröße wie 10x10.", size),
+        CompactError::NoCells => "Ein Brett braucht mindestens eine Zeile und eine Spalte.".to_string(),
+        CompactError::TooLarge { rows, columns } => format!("Ein {}x{} Brett ist größer als die {} Zeilen und Spalten, die ein Brett haben kann.", rows, columns, MAX_BOARD_SIZE),
+        CompactError::UnknownCharacter(character) => format!("'{}' ist kein Feld eines kompakten Bretts.", character),
+        CompactError::WrongCellCount { expected, found } => format!("Das Brett hat {} Felder, angegeben wurden aber {}.", expected, found),
+        CompactError::BadShip(ship) => format!("'{}' ist kein Schiff auf diesem Brett.", ship),
+    },
+    load_error: |e| match e {
+        LoadError::Io(e) => format!("Der Spielstand konnte nicht gelesen werden: {}", e),
+        LoadError::Format(e) => format!("Die Datei ist kein gültiger Spielstand: {}", e),
+        LoadError::GridSize => "Das gespeicherte Raster passt nicht zur Brettgröße.".to_string(),
+        LoadError::ShipOffBoard(kind) => format!("Das gespeicherte Schiff {} liegt außerhalb des Bretts.", (GERMAN.ship)(*kind)),
+        LoadError::ShipMismatch(kind) => format!("Das gespeicherte Schiff {} passt nicht zum Raster.", (GERMAN.ship)(*kind)),
+        LoadError::ShipOverlap(kind) => format!("Das gespeicherte Schiff {} überlappt ein anderes Schiff.", (GERMAN.ship)(*kind)),
+        LoadError::StrayShipCell(position) => format!("Das gespeicherte Raster zeigt auf {},{} ein Schiff, das keinem gehört.", position.row, position.column),
+        LoadError::ShotOffBoard(position) => format!("Der gespeicherte Verlauf hat einen Schuss auf {},{}, außerhalb des Bretts.", position.row, position.column),
+    },
+    turn_time_ignored: "--turn-time gilt nur beim Eintippen von Koordinaten, mit --cursor wird es ignoriert",
+    board_dimensions: |min, max| format!("Zeilen und Spalten müssen Zahlen von {} bis {} sein", min, max),
+    host_or_connect: "erwartet wird --host <port> oder --connect <adresse>",
+};
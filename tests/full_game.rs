@@ -0,0 +1,175 @@
+use battleship::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn ship_cells(board: &Board) -> Vec<Position> {
+    board.ships.iter().flat_map(|ship| ship.cells.iter().copied()).collect()
+}
+
+fn water_cells(board: &Board) -> Vec<Position> {
+    let ships = ship_cells(board);
+    (0..board.rows)
+        .flat_map(|row| (0..board.columns).map(move |column| Position { row, column }))
+        .filter(|position| !ships.contains(position))
+        .collect()
+}
+
+#[test]
+fn known_fleet_is_over_exactly_on_the_last_hit() {
+    let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+    board.place_ship_at(ShipKind::Destroyer, Position { row: 0, column: 0 }, 2, Orientation::Horizontal).unwrap();
+    board.place_ship_at(ShipKind::Submarine, Position { row: 2, column: 5 }, 3, Orientation::Vertical).unwrap();
+    board.place_ship_at(ShipKind::Battleship, Position { row: 9, column: 6 }, 4, Orientation::Horizontal).unwrap();
+    board.place_ship_at(ShipKind::Carrier, Position { row: 3, column: 9 }, 5, Orientation::Vertical).unwrap();
+
+    for position in water_cells(&board) {
+        assert!(matches!(board.fire(position), Ok(FireResult::Miss)));
+        assert!(!board.game_over());
+    }
+
+    let cells = ship_cells(&board);
+    for (i, &position) in cells.iter().enumerate() {
+        assert!(!board.game_over(), "over before the last ship cell was hit");
+        assert!(matches!(board.fire(position), Ok(FireResult::Hit | FireResult::Sunk(_))));
+        assert_eq!(board.game_over(), i + 1 == cells.len());
+    }
+}
+
+#[test]
+fn every_ship_reports_sunk_once() {
+    let mut board = Board::new(DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE, BoardVisibility::Hidden, Rules::default());
+    board.try_place_fleet(&NAVY_FLEET.to_vec(), &mut StdRng::seed_from_u64(7)).unwrap();
+
+    let mut sunk = Vec::new();
+    for position in ship_cells(&board) {
+        if let Ok(FireResult::Sunk(kind)) = board.fire(position) {
+            sunk.push(kind.to_string());
+        }
+    }
+
+    let mut expected: Vec<String> = NAVY_FLEET.iter().map(|(kind, _)| kind.to_string()).collect();
+    sunk.sort();
+    expected.sort();
+    assert_eq!(sunk, expected);
+    assert!(board.game_over());
+}
+
+#[test]
+fn seeded_game_plays_out_against_the_computer() {
+    let fleet = CLASSIC_FLEET.to_vec();
+    let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 42, GameMode::Classic, Difficulty::Hard, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+    let targets = ship_cells(&game.computer_board);
+
+    //The player only fires at ships, the computer gets a turn after every shot
+    for (i, &position) in targets.iter().enumerate() {
+        let result = game.computer_board.fire(position).unwrap();
+        game.record_shot(Player::Human, position, result);
+        if game.computer_board.game_over() {
+            assert_eq!(i + 1, targets.len());
+            break;
+        }
+
+        let shot = opponent_salvo(game.opponent.as_mut(), &game.player_board, 1)[0];
+        let result = game.player_board.fire(shot).unwrap();
+        game.opponent.record(shot, result);
+        game.record_shot(Player::Computer, shot, result);
+        assert!(!game.player_board.game_over(), "the computer cannot sink 14 cells in 13 shots");
+    }
+
+    assert!(game.computer_board.game_over());
+    assert_eq!(game.history.len(), 2 * targets.len() - 1);
+}
+
+#[test]
+fn same_seed_same_layout() {
+    let fleet = CLASSIC_FLEET.to_vec();
+    let new_game = || Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 1234, GameMode::Classic, Difficulty::Medium, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+    let (a, b) = (new_game(), new_game());
+
+    assert_eq!(ship_cells(&a.player_board), ship_cells(&b.player_board));
+    assert_eq!(ship_cells(&a.computer_board), ship_cells(&b.computer_board));
+}
+
+#[test]
+fn ai_against_ai_plays_to_a_winner() {
+    let fleet = CLASSIC_FLEET.to_vec();
+
+    for seed in 0..5 {
+        let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), seed, GameMode::Salvo, Difficulty::Medium, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+        let mut challenger = Difficulty::Easy.strategy(StdRng::seed_from_u64(seed), AiState::default());
+
+        let winner = game.play_out(challenger.as_mut()).expect("someone wins");
+        let loser = match winner {
+            Player::Human => &game.player_board,
+            Player::Computer => &game.computer_board,
+        };
+        assert!(!loser.game_over());
+        assert!(game.player_board.game_over() || game.computer_board.game_over());
+    }
+}
+
+#[test]
+fn turn_limit_goes_to_the_fleet_with_more_afloat() {
+    let fleet = CLASSIC_FLEET.to_vec();
+    let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 7, GameMode::Classic, Difficulty::Hard, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+    game.max_turns = Some(15); //Too few for either side to sink a whole fleet with one shot a turn
+    let mut challenger = Difficulty::Easy.strategy(StdRng::seed_from_u64(7), AiState::default());
+
+    let winner = game.play_out(challenger.as_mut());
+    assert_eq!(game.turns, 30);
+    assert!(!game.player_board.game_over() && !game.computer_board.game_over());
+    assert_eq!(winner, game.leader());
+}
+
+#[test]
+fn stepped_game_answers_every_shot_until_a_fleet_is_sunk() {
+    let fleet = CLASSIC_FLEET.to_vec();
+    let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 42, GameMode::Classic, Difficulty::Hard, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+    let targets = ship_cells(&game.computer_board);
+
+    for (i, &position) in targets.iter().enumerate() {
+        let outcome = game.step(InputAction::Fire(position));
+        assert_eq!((outcome.shots[0].0, outcome.shots[0].1), (Player::Human, position));
+        if outcome.over {
+            assert_eq!(i + 1, targets.len());
+            assert_eq!(outcome.winner, Some(Player::Human));
+            assert_eq!(outcome.shots.len(), 1); //No reply to the winning shot
+            break;
+        }
+        assert_eq!(outcome.shots.len(), 2);
+        assert_eq!(outcome.shots[1].0, Player::Computer);
+        assert_eq!(outcome.turn, Player::Human);
+    }
+
+    assert_eq!(game.player_stats.turns, targets.len());
+    assert_eq!(game.history.len(), 2 * targets.len() - 1);
+    let outcome = game.step(InputAction::Auto); //A finished game stays finished
+    assert!(outcome.over && outcome.shots.is_empty());
+}
+
+#[test]
+fn salvo_steps_wait_for_the_whole_salvo() {
+    let fleet = CLASSIC_FLEET.to_vec();
+    let mut game = Game::new((DEFAULT_BOARD_SIZE, DEFAULT_BOARD_SIZE), 3, GameMode::Salvo, Difficulty::Easy, Rules::default(), &fleet, PlayerPlacement::Random).unwrap();
+    let outside = Position { row: 0, column: DEFAULT_BOARD_SIZE };
+    let outcome = game.step(InputAction::Fire(outside));
+    assert_eq!(outcome.off_board, Some(OutOfBounds(outside)));
+    assert!(outcome.shots.is_empty());
+
+    for column in 0..fleet.len() - 1 {
+        let outcome = game.step(InputAction::Fire(Position { row: 0, column }));
+        assert_eq!(outcome.shots.len(), 1);
+        assert_eq!(outcome.turn, Player::Human);
+    }
+    let outcome = game.step(InputAction::Fire(Position { row: 0, column: fleet.len() - 1 }));
+    assert_eq!(outcome.shots.len(), 1 + fleet.len()); //The last of ours, then a salvo from the untouched computer fleet
+    assert_eq!(outcome.turn, Player::Human);
+    assert_eq!((game.turns, game.player_stats.turns, game.computer_stats.turns), (2, 1, 1));
+
+    assert!(game.step(InputAction::Scan(Axis::Row, 5)).scanned.is_none()); //None to spend
+    game.player_scans = 1;
+    let outcome = game.step(InputAction::Scan(Axis::Row, 5));
+    assert!(outcome.scanned.is_some());
+    assert_eq!(game.player_scans, 0);
+    assert_eq!(game.player_stats.turns, 2); //A scan takes the whole turn
+}